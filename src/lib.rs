@@ -4,9 +4,19 @@
 //! tags ID3v2.4 en archivos MP3, incluyendo metadatos básicos, carátulas, lyrics,
 //! URLs y metadatos específicos de Apple.
 
-use id3::frame::{Content, Lyrics, Picture, PictureType};
-use id3::{Frame, Tag, TagLike};
-use std::path::Path;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use id3::frame::{
+    Chapter, Comment, Content, ExtendedLink, ExtendedText, InvolvedPeopleList, Lyrics, Picture,
+    PictureType, SynchronisedLyrics, SynchronisedLyricsType, TableOfContents, TimestampFormat,
+    Unknown,
+};
+use id3::{Frame, Tag, TagLike, Timestamp, Version};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
 
 /// Aplica los metadatos especificados al tag ID3
 ///
@@ -123,18 +133,20 @@ pub fn apply_metadata(
 
 /// Añade letras (lyrics) al tag
 ///
-/// Las letras se almacenan en un frame USLT (Unsynchronised lyrics)
-/// con código de idioma "spa" (español).
+/// Las letras se almacenan en un frame USLT (Unsynchronised lyrics) con el
+/// código de idioma ISO 639-2 (`lang`) y la descripción indicados. Un frame
+/// con el mismo idioma y descripción que uno existente lo reemplaza, por lo
+/// que pueden convivir letras en varios idiomas.
 ///
 /// # Retorna
 ///
 /// `true` si se añadió el frame correctamente
-pub fn add_lyrics(tag: &mut Tag, text: &str) -> bool {
+pub fn add_lyrics(tag: &mut Tag, text: &str, lang: &str, description: &str) -> bool {
     let lyrics_frame = Frame::with_content(
         "USLT",
         Content::Lyrics(Lyrics {
-            lang: "spa".to_string(),
-            description: String::new(),
+            lang: lang.to_string(),
+            description: description.to_string(),
             text: text.to_string(),
         }),
     );
@@ -143,6 +155,350 @@ pub fn add_lyrics(tag: &mut Tag, text: &str) -> bool {
     true
 }
 
+/// Añade letras sincronizadas (SYLT) al tag
+///
+/// `lines` contiene pares (milisegundos, texto), como los que devuelve
+/// [`parse_lrc`]. Un frame SYLT con el mismo idioma que uno existente lo
+/// reemplaza.
+///
+/// # Retorna
+///
+/// `true` si se añadió el frame correctamente
+pub fn add_synced_lyrics(
+    tag: &mut Tag,
+    lines: &[(u32, String)],
+    lang: &str,
+    description: &str,
+) -> bool {
+    tag.add_frame(SynchronisedLyrics {
+        lang: lang.to_string(),
+        timestamp_format: TimestampFormat::Ms,
+        content_type: SynchronisedLyricsType::Lyrics,
+        description: description.to_string(),
+        content: lines.to_vec(),
+    });
+    true
+}
+
+/// Interpreta un archivo LRC y devuelve sus líneas como pares
+/// (milisegundos, texto) ordenados por tiempo
+///
+/// * Marcas `[mm:ss]`, `[mm:ss.xx]` y `[mm:ss.xxx]`; una línea puede tener
+///   varias marcas (`[00:12.00][01:30.00]Estribillo`)
+/// * La etiqueta `[offset:±ms]` adelanta (positivo) o retrasa (negativo)
+///   todas las líneas
+/// * El resto de etiquetas (`[ar:...]`, `[ti:...]`...) y las líneas sin
+///   marca de tiempo se ignoran
+///
+/// # Errores
+///
+/// Retorna un error si alguna marca de tiempo no es válida o el archivo no
+/// contiene ninguna línea sincronizada.
+pub fn parse_lrc(text: &str) -> Result<Vec<(u32, String)>, String> {
+    let mut offset: i64 = 0;
+    let mut lines = Vec::new();
+
+    for (number, line) in text.lines().enumerate() {
+        let mut rest = line.trim();
+        let mut times = Vec::new();
+
+        while let Some(inner) = rest.strip_prefix('[') {
+            let Some((tag, after)) = inner.split_once(']') else {
+                break;
+            };
+            if let Some(value) = tag.strip_prefix("offset:") {
+                offset = value
+                    .trim()
+                    .parse()
+                    .map_err(|_| format!("Línea {}: offset '{}' no válido", number + 1, value))?;
+            } else if tag.starts_with(|c: char| c.is_ascii_digit()) {
+                let time = parse_lrc_time(tag).ok_or_else(|| {
+                    format!(
+                        "Línea {}: marca de tiempo '[{}]' no válida",
+                        number + 1,
+                        tag
+                    )
+                })?;
+                times.push(time);
+            }
+            rest = after;
+        }
+
+        for time in times {
+            let time = (i64::from(time) - offset).clamp(0, i64::from(u32::MAX)) as u32;
+            lines.push((time, rest.trim().to_string()));
+        }
+    }
+
+    if lines.is_empty() {
+        return Err("El archivo LRC no contiene líneas con marca de tiempo".to_string());
+    }
+
+    lines.sort_by_key(|(time, _)| *time);
+    Ok(lines)
+}
+
+/// Convierte una marca `mm:ss[.xx]` a milisegundos
+fn parse_lrc_time(text: &str) -> Option<u32> {
+    let (minutes, seconds) = text.split_once(':')?;
+    let (seconds, fraction) = match seconds.split_once('.') {
+        Some((seconds, fraction)) => (seconds, fraction),
+        None => (seconds, ""),
+    };
+
+    let minutes: u32 = minutes.parse().ok()?;
+    let seconds: u32 = seconds.parse().ok()?;
+    if seconds >= 60 || fraction.len() > 3 || !fraction.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    // ".5" son 500 ms, ".05" son 50 ms y ".005" son 5 ms
+    let millis = match fraction.len() {
+        0 => 0,
+        len => fraction.parse::<u32>().ok()? * 10u32.pow(3 - len as u32),
+    };
+
+    Some(minutes * 60_000 + seconds * 1000 + millis)
+}
+
+/// Formatea una marca de tiempo en milisegundos como `mm:ss.xx`
+pub fn format_lrc_time(millis: u32) -> String {
+    format!(
+        "{:02}:{:02}.{:02}",
+        millis / 60_000,
+        millis / 1000 % 60,
+        millis % 1000 / 10
+    )
+}
+
+/// Genera el contenido de un archivo LRC a partir de un frame SYLT
+///
+/// # Errores
+///
+/// Retorna un error si las marcas de tiempo están en frames MPEG, que no se
+/// pueden convertir a tiempo sin analizar el audio.
+pub fn format_lrc(lyrics: &SynchronisedLyrics) -> Result<String, String> {
+    if lyrics.timestamp_format != TimestampFormat::Ms {
+        return Err(
+            "Las marcas de tiempo están en frames MPEG y no se pueden exportar a LRC".to_string(),
+        );
+    }
+
+    let mut text = String::new();
+    for (time, line) in &lyrics.content {
+        text.push_str(&format!("[{}]{}\n", format_lrc_time(*time), line));
+    }
+    Ok(text)
+}
+
+/// Interpreta un número de pista o temporada con total opcional (`3` o `3/12`)
+///
+/// # Errores
+///
+/// Retorna un error si alguna parte no es un número o el número supera al
+/// total.
+pub fn parse_number_with_total(text: &str) -> Result<(u32, Option<u32>), String> {
+    let parse = |part: &str| {
+        part.trim()
+            .parse::<u32>()
+            .map_err(|_| format!("'{}' no es un número válido (usa N o N/TOTAL)", text))
+    };
+
+    match text.split_once('/') {
+        Some((number, total)) => {
+            let (number, total) = (parse(number)?, parse(total)?);
+            if number > total {
+                return Err(format!("{} es mayor que el total {}", number, total));
+            }
+            Ok((number, Some(total)))
+        }
+        None => Ok((parse(text)?, None)),
+    }
+}
+
+/// Establece el total de pistas (TRCK `3/12`) y de discos o temporadas
+/// (TPOS `1/2`)
+///
+/// `track` y `disc` son los números que se acaban de fijar en el tag: sin un
+/// total nuevo, se comprueban contra el total que ya tenía.
+///
+/// # Errores
+///
+/// Retorna un error si el tag no tiene el número correspondiente o este
+/// supera al total, nuevo o existente.
+pub fn apply_totals(
+    tag: &mut Tag,
+    track: Option<u32>,
+    track_total: Option<u32>,
+    disc: Option<u32>,
+    disc_total: Option<u32>,
+) -> Result<bool, String> {
+    let mut changed = false;
+
+    if let Some(total) = track_total {
+        match tag.track() {
+            Some(track) if track <= total => tag.set_total_tracks(total),
+            Some(track) => return Err(format!("La pista {} supera el total {}", track, total)),
+            None => return Err("No se puede fijar el total de pistas sin número de pista".into()),
+        }
+        changed = true;
+    } else if let Some(track) = track
+        && let Some(total) = tag.total_tracks()
+        && track > total
+    {
+        return Err(format!("La pista {} supera el total {}", track, total));
+    }
+
+    if let Some(total) = disc_total {
+        match tag.disc() {
+            Some(disc) if disc <= total => tag.set_total_discs(total),
+            Some(disc) => return Err(format!("La temporada {} supera el total {}", disc, total)),
+            None => {
+                return Err(
+                    "No se puede fijar el total de temporadas sin número de temporada".into(),
+                );
+            }
+        }
+        changed = true;
+    } else if let Some(disc) = disc
+        && let Some(total) = tag.total_discs()
+        && disc > total
+    {
+        return Err(format!("La temporada {} supera el total {}", disc, total));
+    }
+
+    Ok(changed)
+}
+
+/// Formatea un número con su total si existe (`3` o `3/12`)
+fn number_with_total(number: u32, total: Option<u32>) -> String {
+    match total {
+        Some(total) => format!("{}/{}", number, total),
+        None => number.to_string(),
+    }
+}
+
+/// Valida un código de idioma ISO 639-2 (tres letras, p. ej. "spa", "eng")
+///
+/// Devuelve el código en minúsculas.
+///
+/// # Errores
+///
+/// Retorna un error si el código no tiene exactamente tres letras ASCII.
+pub fn validate_language(lang: &str) -> Result<String, String> {
+    if lang.len() != 3 || !lang.chars().all(|c| c.is_ascii_alphabetic()) {
+        return Err(format!(
+            "'{}' no es un código de idioma ISO 639-2 válido (tres letras, p. ej. spa)",
+            lang
+        ));
+    }
+    Ok(lang.to_lowercase())
+}
+
+/// Decodifica un texto leído de un archivo detectando su codificación
+///
+/// * UTF-8, con o sin BOM (`EF BB BF`)
+/// * UTF-16 LE o BE con BOM (`FF FE` / `FE FF`)
+/// * UTF-16 LE o BE sin BOM, si el texto tiene caracteres ASCII (espacios,
+///   saltos de línea...): sus bytes nulos delatan la codificación. Un texto
+///   sin BOM y sin ningún carácter ASCII no se puede distinguir de UTF-8
+///
+/// Los saltos de línea `\r\n` se normalizan a `\n`; el resto del texto
+/// (incluidos los saltos de línea finales) se conserva tal cual.
+///
+/// # Errores
+///
+/// Retorna un error si el contenido no es UTF-8 válido ni UTF-16.
+pub fn decode_text(bytes: &[u8]) -> Result<String, String> {
+    let text = match bytes {
+        [0xEF, 0xBB, 0xBF, rest @ ..] => String::from_utf8(rest.to_vec()).ok(),
+        [0xFF, 0xFE, rest @ ..] => decode_utf16(rest, u16::from_le_bytes),
+        [0xFE, 0xFF, rest @ ..] => decode_utf16(rest, u16::from_be_bytes),
+        _ => match utf16_without_bom(bytes) {
+            Some(from_bytes) => decode_utf16(bytes, from_bytes),
+            None => String::from_utf8(bytes.to_vec()).ok(),
+        },
+    }
+    .ok_or("El texto no está codificado en UTF-8 ni en UTF-16")?;
+
+    Ok(text.replace("\r\n", "\n"))
+}
+
+/// Detecta UTF-16 sin BOM por la posición de los bytes nulos
+///
+/// Un texto normal no contiene bytes nulos; en UTF-16 cada carácter ASCII
+/// tiene uno, en la posición impar (LE) o par (BE).
+fn utf16_without_bom(bytes: &[u8]) -> Option<fn([u8; 2]) -> u16> {
+    if bytes.is_empty() || !bytes.len().is_multiple_of(2) {
+        return None;
+    }
+    let nulls = |parity: usize| {
+        bytes
+            .iter()
+            .skip(parity)
+            .step_by(2)
+            .filter(|b| **b == 0)
+            .count()
+    };
+    let (even, odd) = (nulls(0), nulls(1));
+    // Los caracteres U+0100, U+0200... producen algún nulo en la otra posición
+    if odd > 0 && even * 10 <= odd {
+        Some(u16::from_le_bytes)
+    } else if even > 0 && odd * 10 <= even {
+        Some(u16::from_be_bytes)
+    } else {
+        None
+    }
+}
+
+fn decode_utf16(bytes: &[u8], from_bytes: fn([u8; 2]) -> u16) -> Option<String> {
+    if !bytes.len().is_multiple_of(2) {
+        return None;
+    }
+    let units: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|pair| from_bytes([pair[0], pair[1]]))
+        .collect();
+    String::from_utf16(&units).ok()
+}
+
+/// Lee un texto largo desde un archivo, o desde la entrada estándar si la
+/// ruta es `-`, y lo decodifica con [`decode_text`]
+///
+/// # Errores
+///
+/// Retorna un error si no se puede leer la fuente o su codificación no es
+/// válida.
+pub fn read_text_source(path: &Path) -> Result<String, Box<dyn std::error::Error>> {
+    let bytes = if path == Path::new("-") {
+        let mut buffer = Vec::new();
+        std::io::stdin().read_to_end(&mut buffer)?;
+        buffer
+    } else {
+        fs::read(path).map_err(|e| format!("No se pudo leer '{}': {}", path.display(), e))?
+    };
+
+    let text = decode_text(&bytes).map_err(|e| format!("'{}': {}", path.display(), e))?;
+    Ok(text)
+}
+
+/// Añade un comentario (COMM) al tag
+///
+/// Un tag puede tener varios comentarios; se identifican por el par
+/// (idioma, descripción), de modo que un comentario con el mismo idioma y
+/// descripción que uno existente lo reemplaza.
+///
+/// # Retorna
+///
+/// `true` si se añadió el frame correctamente
+pub fn add_comment(tag: &mut Tag, text: &str, lang: &str, description: &str) -> bool {
+    tag.add_frame(Comment {
+        lang: lang.to_string(),
+        description: description.to_string(),
+        text: text.to_string(),
+    });
+    true
+}
+
 /// Añade URL al tag (WOAR - Official artist/performer webpage)
 ///
 /// # Retorna
@@ -202,6 +558,257 @@ pub fn add_apple_metadata(
     changed
 }
 
+/// Metadatos de podcast de iTunes
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct PodcastMetadata<'a> {
+    /// Marca el archivo como episodio de podcast (PCST)
+    pub podcast: bool,
+    /// Categoría (TCAT)
+    pub category: Option<&'a str>,
+    /// Descripción larga (TDES)
+    pub description: Option<&'a str>,
+    /// Palabras clave separadas por comas (TKWD)
+    pub keywords: Option<&'a str>,
+    /// Identificador único del episodio (TGID)
+    pub episode_id: Option<&'a str>,
+    /// URL del feed (WFED)
+    pub feed_url: Option<&'a str>,
+}
+
+/// Añade los frames de podcast de iTunes al tag
+///
+/// PCST no es un frame de texto: iTunes lo escribe como un entero de 4
+/// bytes con valor 1.
+///
+/// # Retorna
+///
+/// `true` si se aplicó al menos un cambio
+pub fn add_podcast_metadata(tag: &mut Tag, metadata: &PodcastMetadata) -> bool {
+    let mut changed = false;
+
+    // PCST - Podcast flag (los frames Unknown no se comparan, así que
+    // add_frame no sustituiría el anterior)
+    if metadata.podcast {
+        tag.remove("PCST");
+        tag.add_frame(Frame::with_content(
+            "PCST",
+            Content::Unknown(Unknown {
+                data: vec![0, 0, 0, 1],
+                version: Version::Id3v24,
+            }),
+        ));
+        changed = true;
+    }
+
+    // TCAT, TDES, TKWD y TGID - Frames de texto
+    for (id, value) in [
+        ("TCAT", metadata.category),
+        ("TDES", metadata.description),
+        ("TKWD", metadata.keywords),
+        ("TGID", metadata.episode_id),
+    ] {
+        if let Some(value) = value {
+            tag.set_text(id, value);
+            changed = true;
+        }
+    }
+
+    // WFED - Podcast feed URL
+    if let Some(url) = metadata.feed_url {
+        tag.remove("WFED");
+        tag.add_frame(Frame::with_content("WFED", Content::Link(url.to_string())));
+        changed = true;
+    }
+
+    changed
+}
+
+/// Interpreta una asignación `DESCRIPCIÓN=valor` de `--txxx` y `--wxxx`
+///
+/// La descripción termina en el primer `=`, por lo que el valor puede
+/// contener más signos `=` (útil para URLs con parámetros).
+///
+/// # Errores
+///
+/// Retorna un error si falta el `=` o la descripción o el valor están vacíos.
+pub fn parse_description_assignment(spec: &str) -> Result<(String, String), String> {
+    let (description, value) = spec
+        .split_once('=')
+        .ok_or_else(|| format!("'{}' no tiene el formato DESCRIPCIÓN=valor", spec))?;
+    if description.trim().is_empty() {
+        return Err(format!("'{}' no tiene descripción", spec));
+    }
+    if value.is_empty() {
+        return Err(format!("El valor de '{}' está vacío", description));
+    }
+    Ok((description.to_string(), value.to_string()))
+}
+
+/// Añade un frame de texto definido por el usuario (TXXX)
+///
+/// Reemplaza el TXXX existente con la misma descripción.
+pub fn add_user_text(tag: &mut Tag, description: &str, value: &str) -> bool {
+    tag.add_frame(ExtendedText {
+        description: description.to_string(),
+        value: value.to_string(),
+    });
+    true
+}
+
+/// Añade un frame de URL definido por el usuario (WXXX)
+///
+/// Reemplaza el WXXX existente con la misma descripción.
+pub fn add_user_url(tag: &mut Tag, description: &str, url: &str) -> bool {
+    tag.add_frame(ExtendedLink {
+        description: description.to_string(),
+        link: url.to_string(),
+    });
+    true
+}
+
+/// Elimina los frames TXXX o WXXX con la descripción indicada
+///
+/// # Retorna
+///
+/// `true` si se eliminó al menos un frame
+pub fn remove_user_frame(tag: &mut Tag, id: &str, description: &str) -> bool {
+    let mut removed = false;
+    for frame in tag.remove(id) {
+        let matches = match frame.content() {
+            Content::ExtendedText(text) => text.description == description,
+            Content::ExtendedLink(link) => link.description == description,
+            _ => false,
+        };
+        if matches {
+            removed = true;
+        } else {
+            tag.add_frame(frame);
+        }
+    }
+    removed
+}
+
+/// Identificadores de frame declarados en ID3v2.4, más las extensiones de
+/// iTunes de uso común (TCMP, TSO2, TSOC y los frames de podcast)
+pub const V24_FRAME_IDS: &[&str] = &[
+    "AENC", "APIC", "ASPI", "COMM", "COMR", "ENCR", "EQU2", "ETCO", "GEOB", "GRID", "LINK", "MCDI",
+    "MLLT", "OWNE", "PRIV", "PCNT", "POPM", "POSS", "RBUF", "RVA2", "RVRB", "SEEK", "SIGN", "SYLT",
+    "SYTC", "TALB", "TBPM", "TCOM", "TCON", "TCOP", "TDEN", "TDLY", "TDOR", "TDRC", "TDRL", "TDTG",
+    "TENC", "TEXT", "TFLT", "TIPL", "TIT1", "TIT2", "TIT3", "TKEY", "TLAN", "TLEN", "TMCL", "TMED",
+    "TMOO", "TOAL", "TOFN", "TOLY", "TOPE", "TOWN", "TPE1", "TPE2", "TPE3", "TPE4", "TPOS", "TPRO",
+    "TPUB", "TRCK", "TRSN", "TRSO", "TSOA", "TSOP", "TSOT", "TSRC", "TSSE", "TSST", "TXXX", "UFID",
+    "USER", "USLT", "WCOM", "WCOP", "WOAF", "WOAR", "WOAS", "WORS", "WPAY", "WPUB", "WXXX", "TCMP",
+    "TSO2", "TSOC", "PCST", "TCAT", "TDES", "TKWD", "TGID", "WFED",
+];
+
+/// Valida un identificador de frame ID3v2.4
+///
+/// Acepta el identificador en mayúsculas o minúsculas y lo devuelve en
+/// mayúsculas.
+///
+/// # Errores
+///
+/// Retorna un error si el identificador no tiene cuatro caracteres o no
+/// pertenece a la lista de frames de ID3v2.4.
+pub fn validate_frame_id(id: &str) -> Result<String, String> {
+    let id = id.trim().to_uppercase();
+    if id.len() != 4
+        || !id
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+    {
+        return Err(format!(
+            "'{}' no es un identificador de frame válido (4 caracteres, p. ej. TKEY)",
+            id
+        ));
+    }
+    if !V24_FRAME_IDS.contains(&id.as_str()) {
+        return Err(format!("El frame '{}' no existe en ID3v2.4", id));
+    }
+    Ok(id)
+}
+
+/// Interpreta una asignación `ID=valor` del argumento `--frame`
+///
+/// # Errores
+///
+/// Retorna un error si falta el `=` o el frame no se puede editar con
+/// [`set_raw_frame`].
+pub fn parse_frame_assignment(spec: &str) -> Result<(String, String), String> {
+    let (id, value) = spec
+        .split_once('=')
+        .ok_or_else(|| format!("'{}' no tiene el formato ID=valor", spec))?;
+    let id = validate_editable_frame(id, value)?;
+    Ok((id, value.to_string()))
+}
+
+/// Frames de texto que guardan pares rol/persona en lugar de texto libre
+const INVOLVED_PEOPLE_FRAMES: &[&str] = &["TIPL", "TMCL", "IPLS"];
+
+/// Comprueba que el frame es de texto (`T...`) o de URL (`W...`) y que el
+/// valor no está vacío; el resto de frames tienen contenido binario o
+/// estructurado y requieren su opción dedicada. Las listas de personas
+/// (TIPL, TMCL, IPLS) se rechazan y las fechas deben ser marcas de tiempo
+/// válidas
+fn validate_editable_frame(id: &str, value: &str) -> Result<String, String> {
+    let normalized = id.trim().to_uppercase();
+    if INVOLVED_PEOPLE_FRAMES.contains(&normalized.as_str()) {
+        return Err(format!(
+            "El frame '{}' es una lista de personas y roles y no se puede editar con --frame",
+            normalized
+        ));
+    }
+    let id = validate_frame_id(id)?;
+
+    if id == "TXXX" || id == "WXXX" {
+        return Err(format!(
+            "El frame '{}' requiere una descripción y no se puede editar con --frame",
+            id
+        ));
+    }
+    if !id.starts_with('T') && !id.starts_with('W') {
+        return Err(format!(
+            "El frame '{}' no es de texto ni de URL; usa su opción dedicada",
+            id
+        ));
+    }
+    if value.is_empty() {
+        return Err(format!("El valor del frame '{}' está vacío", id));
+    }
+    if TIMESTAMP_FRAMES.contains(&id.as_str()) && !is_valid_timestamp(value) {
+        return Err(format!(
+            "El valor del frame '{}' no es una fecha válida (AAAA[-MM[-DD[THH[:MM[:SS]]]]]): '{}'",
+            id, value
+        ));
+    }
+
+    Ok(id)
+}
+
+/// Establece un frame de texto o de URL por su identificador
+///
+/// Los frames de texto se guardan con [`TagLike::set_text`] y los de URL
+/// como [`Content::Link`]. En ambos casos se reemplazan los frames
+/// existentes con el mismo identificador.
+///
+/// # Errores
+///
+/// Retorna un error si el identificador no existe en ID3v2.4, no es un
+/// frame de texto o de URL, es una lista de personas (TIPL, TMCL), el valor
+/// está vacío o, en los frames de fecha (TDRC, TDOR...), no es una fecha
+/// válida.
+pub fn set_raw_frame(tag: &mut Tag, id: &str, value: &str) -> Result<(), String> {
+    let id = validate_editable_frame(id, value)?;
+
+    if id.starts_with('W') {
+        tag.remove(&id);
+        tag.add_frame(Frame::with_content(&id, Content::Link(value.to_string())));
+    } else {
+        tag.set_text(&id, value);
+    }
+    Ok(())
+}
+
 /// Crea un Picture frame desde datos de imagen
 ///
 /// La imagen se configura como carátula frontal (CoverFront) con
@@ -222,6 +829,8 @@ pub fn create_picture_frame(data: Vec<u8>, mime_type: &str) -> Picture {
 /// * JPG/JPEG → image/jpeg
 /// * PNG → image/png
 /// * WEBP → image/webp
+/// * GIF → image/gif
+/// * BMP → image/bmp
 ///
 /// # Errores
 ///
@@ -237,152 +846,529 @@ pub fn detect_mime_type(path: &Path) -> Result<&'static str, String> {
         "jpg" | "jpeg" => Ok("image/jpeg"),
         "png" => Ok("image/png"),
         "webp" => Ok("image/webp"),
+        "gif" => Ok("image/gif"),
+        "bmp" => Ok("image/bmp"),
         _ => Err(format!("Formato de imagen no soportado: .{}", extension)),
     }
 }
 
-/// Añade una carátula al tag desde un archivo
+/// Tamaños de las cabeceras DIB de BMP conocidas (BITMAPCOREHEADER,
+/// BITMAPINFOHEADER y sus variantes V2 a V5)
+const BMP_DIB_HEADER_SIZES: &[u32] = &[12, 40, 52, 56, 64, 108, 124];
+
+/// Comprueba la cabecera de archivo BMP: `BM`, campos reservados a cero,
+/// un tamaño de cabecera DIB conocido y los píxeles tras las cabeceras
+fn is_bmp(data: &[u8]) -> bool {
+    if data.len() < 18 || !data.starts_with(b"BM") || data[6..10] != [0; 4] {
+        return false;
+    }
+    let read_u32 = |offset: usize| {
+        u32::from_le_bytes([
+            data[offset],
+            data[offset + 1],
+            data[offset + 2],
+            data[offset + 3],
+        ])
+    };
+    let pixel_offset = read_u32(10);
+    let dib_size = read_u32(14);
+    BMP_DIB_HEADER_SIZES.contains(&dib_size) && pixel_offset >= 14 + dib_size
+}
+
+/// Detecta el tipo MIME a partir de la cabecera (magic bytes) de la imagen
+///
+/// Reconoce JPEG (SOI), PNG, WEBP (RIFF/WEBP), GIF (87a/89a) y BMP (ver
+/// [`is_bmp`]; `BM` solo no basta).
+pub fn sniff_mime_type(data: &[u8]) -> Option<&'static str> {
+    if data.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        Some("image/jpeg")
+    } else if data.starts_with(&[0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A]) {
+        Some("image/png")
+    } else if data.len() >= 12 && data.starts_with(b"RIFF") && &data[8..12] == b"WEBP" {
+        Some("image/webp")
+    } else if data.starts_with(b"GIF87a") || data.starts_with(b"GIF89a") {
+        Some("image/gif")
+    } else if is_bmp(data) {
+        Some("image/bmp")
+    } else {
+        None
+    }
+}
+
+/// Determina el tipo MIME de una imagen por su contenido, usando la
+/// extensión solo si la cabecera no se reconoce
 ///
-/// El tipo MIME se detecta automáticamente desde la extensión del archivo.
+/// Si el contenido y la extensión no coinciden se usa el tipo del contenido;
+/// ver [`image_mime_mismatch`] para avisar de ello.
 ///
 /// # Errores
 ///
-/// Retorna un error si:
-/// * El formato de imagen no es soportado
-/// * No se puede determinar la extensión
-pub fn add_cover_art(
-    tag: &mut Tag,
-    cover_path: &Path,
-    cover_data: Vec<u8>,
-) -> Result<(), Box<dyn std::error::Error>> {
-    let mime_type =
-        detect_mime_type(cover_path).map_err(|e| format!("{} (soportados: jpg, png, webp)", e))?;
-    let picture = create_picture_frame(cover_data, mime_type);
-    tag.add_frame(picture);
-    Ok(())
+/// Retorna un error si ni el contenido ni la extensión corresponden a un
+/// formato soportado.
+pub fn image_mime_type(path: &Path, data: &[u8]) -> Result<&'static str, String> {
+    match sniff_mime_type(data) {
+        Some(sniffed) => Ok(sniffed),
+        None => detect_mime_type(path)
+            .map_err(|e| format!("{} (soportados: jpg, png, webp, gif, bmp)", e)),
+    }
 }
 
-/// Elimina tags específicos del archivo
+/// Aviso para cuando el contenido de una imagen no corresponde a su extensión
 ///
-/// # Tags soportados (con nombres alternativos en español)
+/// # Retorna
 ///
-/// * title/título - Título de la canción
-/// * artist/artista - Artista
-/// * album/álbum - Álbum
-/// * year/año - Año
-/// * genre/género - Género
-/// * track/pista - Número de pista
-/// * season/temporada - Temporada
-/// * date/fecha - Fecha de grabación
-/// * copyright - Copyright
-/// * composer/compositor - Compositor
-/// * subtitle/subtítulo/description/descripción - Subtítulo
-/// * original_artist/artista_original - Artista original
-/// * album_artist/artista_album - Artista del álbum
-/// * cover/carátula - Carátula
-/// * lyrics/letra - Letra
-/// * url - URL
-/// * compilation/compilación - Flag de compilación
-/// * album_sort/orden_album - Orden de álbum
-/// * artist_sort/orden_artista - Orden de artista
-/// * title_sort/orden_titulo - Orden de título
+/// El mensaje a mostrar, o `None` si coinciden o alguno no se reconoce
+pub fn image_mime_mismatch(path: &Path, data: &[u8]) -> Option<String> {
+    let sniffed = sniff_mime_type(data)?;
+    let expected = detect_mime_type(path).ok()?;
+    (sniffed != expected).then(|| {
+        format!(
+            "'{}' contiene una imagen {} aunque su extensión indica {}; se usa {}",
+            path.display(),
+            sniffed,
+            expected,
+            sniffed
+        )
+    })
+}
+
+/// Nombres de archivo (sin extensión) que se buscan por defecto como carátula
+/// del directorio
+pub const DEFAULT_COVER_NAMES: &[&str] = &["cover", "folder", "front", "album", "artwork"];
+
+/// Extensiones de imagen admitidas como carátula
+const IMAGE_EXTENSIONS: &[&str] = &["jpg", "jpeg", "png", "webp", "gif", "bmp"];
+
+/// Busca la carátula de un directorio a partir de una lista de nombres
 ///
-/// # Retorna
+/// Cada nombre puede indicarse sin extensión (`cover` coincide con
+/// `cover.jpg`, `Cover.PNG`...) o con ella (`folder.jpg`). Se prueban los
+/// nombres en orden y se devuelve la primera coincidencia, sin distinguir
+/// mayúsculas.
+pub fn find_cover_image(dir: &Path, names: &[String]) -> Option<PathBuf> {
+    let mut images: Vec<PathBuf> = fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .filter(|path| {
+            path.extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| IMAGE_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+        })
+        .collect();
+    images.sort();
+
+    names.iter().find_map(|name| {
+        let name = name.trim().to_lowercase();
+        images
+            .iter()
+            .find(|path| {
+                let lower = |part: Option<&std::ffi::OsStr>| {
+                    part.map(|part| part.to_string_lossy().to_lowercase())
+                };
+                lower(path.file_name()).as_deref() == Some(name.as_str())
+                    || lower(path.file_stem()).as_deref() == Some(name.as_str())
+            })
+            .cloned()
+    })
+}
+
+/// Formato al que se recodifica una carátula
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CoverFormat {
+    Jpeg,
+    Png,
+}
+
+/// Opciones de redimensionado y recompresión de carátulas
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CoverProcessing {
+    /// Lado máximo en píxeles; las imágenes mayores se reducen manteniendo la proporción
+    pub max_size: Option<u32>,
+    /// Formato de salida (por defecto, el de la imagen original)
+    pub format: Option<CoverFormat>,
+    /// Calidad JPEG (1-100)
+    pub quality: u8,
+}
+
+impl Default for CoverProcessing {
+    fn default() -> Self {
+        Self {
+            max_size: None,
+            format: None,
+            quality: 85,
+        }
+    }
+}
+
+/// Resultado de procesar una carátula
+#[derive(Debug, Clone)]
+pub struct ProcessedCover {
+    pub data: Vec<u8>,
+    pub mime_type: &'static str,
+    /// Dimensiones originales (ancho, alto)
+    pub original_dimensions: (u32, u32),
+    /// Dimensiones tras el redimensionado (ancho, alto)
+    pub dimensions: (u32, u32),
+}
+
+/// Redimensiona y recodifica una carátula antes de incrustarla
 ///
-/// `true` si se eliminó al menos un tag
-pub fn remove_tags(tag: &mut Tag, tags_to_remove: &[String]) -> bool {
-    let mut changed = false;
+/// La imagen se reduce para que ningún lado supere `max_size` y se codifica
+/// en `format`. Sin formato, los JPEG se mantienen en JPEG y el resto se
+/// codifica en PNG; si además no hay que reducirla, se devuelven los bytes
+/// originales sin recodificar. JPEG no admite transparencia, así que el
+/// canal alfa se descarta al convertir.
+///
+/// # Errores
+///
+/// Retorna un error si la imagen no se puede decodificar o codificar.
+pub fn process_cover(data: &[u8], options: &CoverProcessing) -> Result<ProcessedCover, String> {
+    let image =
+        image::load_from_memory(data).map_err(|e| format!("No se pudo leer la imagen: {}", e))?;
+    let original_dimensions = (image.width(), image.height());
 
-    for tag_name in tags_to_remove {
-        let removed = match tag_name.to_lowercase().as_str() {
-            "title" | "título" => {
-                tag.remove_title();
-                true
-            }
-            "artist" | "artista" => {
-                tag.remove_artist();
-                true
-            }
-            "album" | "álbum" => {
-                tag.remove_album();
-                true
-            }
-            "year" | "año" => {
-                tag.remove_year();
-                true
-            }
-            "genre" | "género" | "genero" => {
-                tag.remove_genre();
-                true
-            }
-            "track" | "pista" => {
-                tag.remove_track();
-                true
-            }
-            "season" | "temporada" => {
-                tag.remove_disc();
-                true
-            }
-            "date" | "fecha" => {
-                tag.remove_date_recorded();
-                true
-            }
-            "copyright" => {
-                tag.remove("TCOP");
-                true
-            }
-            "composer" | "compositor" => {
-                tag.remove("TCOM");
-                true
-            }
-            "subtitle" | "subtítulo" | "subtitulo" | "description" | "descripción"
-            | "descripcion" => {
-                tag.remove("TIT3");
-                true
-            }
-            "original_artist" | "original-artist" | "artista_original" | "artista-original" => {
-                tag.remove("TOPE");
-                true
-            }
-            "album_artist" | "album-artist" | "artista_album" | "artista-album" => {
-                tag.remove_album_artist();
-                true
-            }
-            "cover" | "carátula" | "caratula" => {
-                tag.remove_all_pictures();
-                true
+    let max_size = options
+        .max_size
+        .filter(|&max| image.width() > max || image.height() > max);
+    if max_size.is_none()
+        && options.format.is_none()
+        && let Some(mime_type) = sniff_mime_type(data)
+    {
+        return Ok(ProcessedCover {
+            data: data.to_vec(),
+            mime_type,
+            original_dimensions,
+            dimensions: original_dimensions,
+        });
+    }
+
+    let image = match max_size {
+        Some(max) => image.resize(max, max, image::imageops::FilterType::Lanczos3),
+        None => image,
+    };
+
+    let format = match options.format {
+        Some(format) => format,
+        None => match sniff_mime_type(data) {
+            Some("image/jpeg") => CoverFormat::Jpeg,
+            _ => CoverFormat::Png,
+        },
+    };
+
+    let mut encoded = Vec::new();
+    let result = match format {
+        CoverFormat::Jpeg => {
+            let encoder =
+                image::codecs::jpeg::JpegEncoder::new_with_quality(&mut encoded, options.quality);
+            image.to_rgb8().write_with_encoder(encoder)
+        }
+        CoverFormat::Png => {
+            let encoder = image::codecs::png::PngEncoder::new(&mut encoded);
+            image.write_with_encoder(encoder)
+        }
+    };
+    result.map_err(|e| format!("No se pudo codificar la imagen: {}", e))?;
+
+    Ok(ProcessedCover {
+        data: encoded,
+        mime_type: match format {
+            CoverFormat::Jpeg => "image/jpeg",
+            CoverFormat::Png => "image/png",
+        },
+        original_dimensions,
+        dimensions: (image.width(), image.height()),
+    })
+}
+
+/// Formatea un tamaño en bytes de forma legible (`512 B`, `245.3 KB`, `12.1 MB`)
+pub fn format_bytes(bytes: usize) -> String {
+    const KB: f64 = 1024.0;
+    let size = bytes as f64;
+    if size < KB {
+        format!("{} B", bytes)
+    } else if size < KB * KB {
+        format!("{:.1} KB", size / KB)
+    } else {
+        format!("{:.1} MB", size / (KB * KB))
+    }
+}
+
+/// Lado mínimo en píxeles de la carátula de un podcast en Apple Podcasts
+pub const APPLE_ARTWORK_MIN_SIZE: u32 = 1400;
+
+/// Lado máximo en píxeles de la carátula de un podcast en Apple Podcasts
+pub const APPLE_ARTWORK_MAX_SIZE: u32 = 3000;
+
+/// Número de componentes de color de un JPEG (1 = gris, 3 = YCbCr/RGB,
+/// 4 = CMYK), leído de su cabecera SOF
+fn jpeg_components(data: &[u8]) -> Option<u8> {
+    let mut i = 2;
+    while i + 9 < data.len() {
+        if data[i] != 0xFF {
+            return None;
+        }
+        let marker = data[i + 1];
+        if marker == 0xFF {
+            i += 1;
+            continue;
+        }
+        // SOF0-SOF15, excepto DHT (C4), JPG (C8) y DAC (CC)
+        if (0xC0..=0xCF).contains(&marker) && !matches!(marker, 0xC4 | 0xC8 | 0xCC) {
+            return Some(data[i + 9]);
+        }
+        let length = u16::from_be_bytes([data[i + 2], data[i + 3]]) as usize;
+        i += 2 + length;
+    }
+    None
+}
+
+/// Valida una carátula contra los requisitos de Apple Podcasts
+///
+/// Comprueba que sea JPEG o PNG, cuadrada, de entre 1400 y 3000 píxeles de
+/// lado y en espacio de color RGB (no escala de grises ni CMYK).
+///
+/// # Retorna
+///
+/// La lista de incumplimientos; vacía si la imagen es válida
+pub fn validate_apple_artwork(data: &[u8]) -> Vec<String> {
+    let mut problems = Vec::new();
+
+    let mime_type = sniff_mime_type(data);
+    match mime_type {
+        Some("image/jpeg") | Some("image/png") => {}
+        Some(other) => problems.push(format!("Formato {} no admitido (usa JPEG o PNG)", other)),
+        None => {
+            problems.push("No se reconoce el formato de la imagen (usa JPEG o PNG)".to_string());
+            return problems;
+        }
+    }
+
+    let dimensions = image::ImageReader::new(std::io::Cursor::new(data))
+        .with_guessed_format()
+        .ok()
+        .and_then(|reader| reader.into_dimensions().ok());
+    match dimensions {
+        Some((width, height)) => {
+            if width != height {
+                problems.push(format!("No es cuadrada: {}×{}", width, height));
             }
-            "lyrics" | "letra" => {
-                tag.remove("USLT");
-                true
+            let (small, large) = (width.min(height), width.max(height));
+            if small < APPLE_ARTWORK_MIN_SIZE {
+                problems.push(format!(
+                    "Demasiado pequeña: {}×{} (mínimo {}×{})",
+                    width, height, APPLE_ARTWORK_MIN_SIZE, APPLE_ARTWORK_MIN_SIZE
+                ));
             }
-            "url" => {
-                tag.remove("WOAR");
-                true
+            if large > APPLE_ARTWORK_MAX_SIZE {
+                problems.push(format!(
+                    "Demasiado grande: {}×{} (máximo {}×{})",
+                    width, height, APPLE_ARTWORK_MAX_SIZE, APPLE_ARTWORK_MAX_SIZE
+                ));
             }
-            "compilation" | "compilación" | "compilacion" => {
-                tag.remove("TCMP");
-                true
+        }
+        None => problems.push("No se pudieron leer las dimensiones de la imagen".to_string()),
+    }
+
+    let colour_space = match mime_type {
+        Some("image/jpeg") => match jpeg_components(data) {
+            Some(1) => Some("escala de grises"),
+            Some(4) => Some("CMYK"),
+            _ => None,
+        },
+        // Tipo de color de la cabecera IHDR: 0 = gris, 4 = gris con alfa
+        Some("image/png") => match data.get(25) {
+            Some(0) | Some(4) => Some("escala de grises"),
+            _ => None,
+        },
+        _ => None,
+    };
+    if let Some(colour_space) = colour_space {
+        problems.push(format!("Espacio de color {} (debe ser RGB)", colour_space));
+    }
+
+    problems
+}
+
+/// Añade una carátula al tag desde un archivo
+///
+/// El tipo MIME se detecta desde el contenido de la imagen (ver
+/// [`image_mime_type`]). La imagen se añade como carátula frontal; ver
+/// [`add_picture`] para otros tipos.
+///
+/// # Errores
+///
+/// Retorna un error si ni el contenido ni la extensión corresponden a un
+/// formato soportado.
+pub fn add_cover_art(
+    tag: &mut Tag,
+    cover_path: &Path,
+    cover_data: Vec<u8>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mime_type = image_mime_type(cover_path, &cover_data)?;
+    add_picture(tag, mime_type, cover_data, PictureType::CoverFront, "Cover");
+    Ok(())
+}
+
+/// Añade una imagen APIC del tipo y tipo MIME indicados
+///
+/// Las imágenes existentes del mismo tipo se sustituyen en lugar de
+/// acumularse.
+///
+/// # Retorna
+///
+/// `true` si se ha sustituido una imagen existente del mismo tipo
+pub fn add_picture(
+    tag: &mut Tag,
+    mime_type: &str,
+    data: Vec<u8>,
+    picture_type: PictureType,
+    description: &str,
+) -> bool {
+    let replaced = tag.pictures().any(|p| p.picture_type == picture_type);
+    tag.remove_picture_by_type(picture_type);
+    tag.add_frame(Picture {
+        mime_type: mime_type.to_string(),
+        picture_type,
+        description: description.to_string(),
+        data,
+    });
+    replaced
+}
+
+/// Elimina una única imagen APIC por tipo (`back`, `artist`...) o por su
+/// posición en `show` (`1`, `2`...)
+///
+/// # Errores
+///
+/// Retorna un error si el selector no es un tipo de imagen ni una posición
+/// válida.
+///
+/// # Retorna
+///
+/// `true` si se eliminó alguna imagen
+pub fn remove_picture(tag: &mut Tag, selector: &str) -> Result<bool, String> {
+    if let Ok(position) = selector.trim().parse::<usize>() {
+        let count = tag.pictures().count();
+        if position == 0 || position > count {
+            return Err(format!(
+                "No existe la carátula {} (el archivo tiene {})",
+                position, count
+            ));
+        }
+        // Se retiran todas y se vuelven a añadir las demás en su orden
+        let frames = tag.remove("APIC");
+        for (i, frame) in frames.into_iter().enumerate() {
+            if i + 1 != position {
+                tag.add_frame(frame);
             }
-            "album_sort" | "album-sort" | "orden_album" | "orden-album" => {
-                tag.remove("TSOA");
+        }
+        return Ok(true);
+    }
+
+    let picture_type = validate_picture_type(selector)?;
+    let found = tag.pictures().any(|p| p.picture_type == picture_type);
+    tag.remove_picture_by_type(picture_type);
+    Ok(found)
+}
+
+/// Elimina tags específicos del archivo
+///
+/// # Tags soportados (con nombres alternativos en español)
+///
+/// * title/título - Título de la canción
+/// * artist/artista - Artista
+/// * album/álbum - Álbum
+/// * year/año - Año (TYER)
+/// * genre/género - Género
+/// * track/pista - Número de pista
+/// * season/temporada/disc/disco - Temporada o disco
+/// * track_total/total_pistas - Solo el total de pistas (TRCK `3/12` → `3`)
+/// * season_total/disc_total/total_temporadas - Solo el total de temporadas
+/// * date/fecha - Fecha de grabación (TDRC)
+/// * copyright - Copyright
+/// * composer/compositor - Compositor
+/// * subtitle/subtítulo/description/descripción - Subtítulo
+/// * original_artist/artista_original - Artista original
+/// * album_artist/artista_album - Artista del álbum
+/// * cover/carátula - Todas las carátulas
+/// * cover:TIPO / cover:N - Solo las carátulas de un tipo (`back`, `artist`...) o la N-ésima
+/// * lyrics/letra - Letra
+/// * synced_lyrics/letra_sincronizada - Letra sincronizada (SYLT)
+/// * url - URL
+/// * podcast - Flag de podcast (PCST)
+/// * category/categoría - Categoría del podcast (TCAT)
+/// * long_description/descripción_larga - Descripción larga (TDES)
+/// * keywords/palabras_clave - Palabras clave (TKWD)
+/// * episode_id/guid - Identificador del episodio (TGID)
+/// * feed_url/feed - URL del feed (WFED)
+/// * comment/comentario - Todos los comentarios
+/// * compilation/compilación - Flag de compilación
+/// * album_sort/orden_album - Orden de álbum
+/// * artist_sort/orden_artista - Orden de artista
+/// * title_sort/orden_titulo - Orden de título
+/// * chapters/capítulos - Capítulos y tabla de contenidos (CHAP/CTOC)
+/// * txxx:DESCRIPCIÓN / wxxx:DESCRIPCIÓN - Frame de usuario con esa descripción
+/// * Cualquier ID de frame ID3v2.4 (TKEY, TBPM, APIC...) - Todos los frames con ese ID
+///
+/// Salvo los totales, `cover:` y los frames de usuario, los nombres se
+/// traducen con [`field_frame_ids`].
+///
+/// # Retorna
+///
+/// `true` si se eliminó al menos un tag
+pub fn remove_tags(tag: &mut Tag, tags_to_remove: &[String]) -> bool {
+    let mut changed = false;
+
+    for tag_name in tags_to_remove {
+        let removed = match tag_name.to_lowercase().as_str() {
+            "track_total" | "track-total" | "total_pistas" | "total-pistas" => {
+                tag.remove_total_tracks();
                 true
             }
-            "artist_sort" | "artist-sort" | "orden_artista" | "orden-artista" => {
-                tag.remove("TSOP");
+            "season_total" | "season-total" | "disc_total" | "disc-total" | "total_temporadas"
+            | "total-temporadas" => {
+                tag.remove_total_discs();
                 true
             }
-            "title_sort" | "title-sort" | "orden_titulo" | "orden-titulo" => {
-                tag.remove("TSOT");
-                true
+            name if name.starts_with("cover:") || name.starts_with("carátula:") => {
+                let selector = &name[name.find(':').unwrap_or_default() + 1..];
+                match remove_picture(tag, selector) {
+                    Ok(true) => true,
+                    Ok(false) => {
+                        eprintln!("⚠️  No existe ninguna carátula de tipo '{}'", selector);
+                        false
+                    }
+                    Err(e) => {
+                        eprintln!("⚠️  {}", e);
+                        false
+                    }
+                }
             }
-            _ => {
-                eprintln!(
-                    "⚠️  Tag desconocido: '{}'. Tags válidos: title, artist, album, year, genre, track, season, date, copyright, composer, subtitle, original_artist, album_artist, cover, lyrics, url, compilation, album_sort, artist_sort, title_sort",
-                    tag_name
-                );
-                false
+            name if name.starts_with("txxx:") || name.starts_with("wxxx:") => {
+                let id = name[..4].to_uppercase();
+                let removed = remove_user_frame(tag, &id, &tag_name[5..]);
+                if !removed {
+                    eprintln!("⚠️  No existe {} con descripción '{}'", id, &tag_name[5..]);
+                }
+                removed
             }
+            _ => match field_frame_ids(tag_name) {
+                Ok(ids) => {
+                    for id in &ids {
+                        tag.remove(id);
+                    }
+                    true
+                }
+                Err(_) => {
+                    eprintln!(
+                        "⚠️  Tag desconocido: '{}'. Tags válidos: title, artist, album, year, genre, track, track_total, season, season_total, date, copyright, composer, subtitle, original_artist, album_artist, cover, cover:TIPO, lyrics, synced_lyrics, url, podcast, category, long_description, keywords, episode_id, feed_url, comment, compilation, album_sort, artist_sort, title_sort, chapters, txxx:DESCRIPCIÓN, wxxx:DESCRIPCIÓN o un ID de frame ID3v2.4 (TKEY, TBPM...)",
+                        tag_name
+                    );
+                    false
+                }
+            },
         };
 
         if removed {
@@ -396,34 +1382,132 @@ pub fn remove_tags(tag: &mut Tag, tags_to_remove: &[String]) -> bool {
 
 /// Elimina todos los tags del archivo MP3
 ///
-/// Borra todos los frames ID3 conocidos: metadatos básicos, carátulas,
-/// letras, URLs y metadatos de Apple.
+/// Borra todos los frames del tag, sea cual sea su ID, de modo que los
+/// frames nuevos (comentarios, capítulos, podcast...) no se quedan atrás.
 ///
 /// # Retorna
 ///
 /// `true` si se eliminó al menos un tag
 pub fn remove_all_tags(tag: &mut Tag) -> bool {
-    let has_frames = tag.frames().count() > 0;
-
-    tag.remove_title();
-    tag.remove_artist();
-    tag.remove_album();
-    tag.remove_year();
-    tag.remove_genre();
-    tag.remove_track();
-    tag.remove_disc();
-    tag.remove_date_recorded();
-    tag.remove_album_artist();
-    tag.remove_all_pictures();
-    tag.remove_all_lyrics();
-
-    for frame_id in &[
-        "TCOP", "TCOM", "TIT3", "TOPE", "WOAR", "TCMP", "TSOA", "TSOP", "TSOT",
-    ] {
-        tag.remove(frame_id);
+    let mut ids: Vec<String> = tag.frames().map(|frame| frame.id().to_string()).collect();
+    ids.sort_unstable();
+    ids.dedup();
+
+    for id in &ids {
+        tag.remove(id);
+    }
+
+    !ids.is_empty()
+}
+
+/// Traduce un nombre de tag (en inglés o español) a los IDs de frame que lo
+/// almacenan
+///
+/// Es la tabla de nombres que comparten [`remove_tags`] y [`copy_tags`]:
+/// `year` es TYER y `date` es TDRC, igual que en `edit`, y
+/// `chapters`/`capítulos` incluye CHAP y CTOC. Cualquier ID de frame
+/// ID3v2.4 se acepta tal cual.
+///
+/// # Errores
+///
+/// Retorna un error si el nombre no corresponde a ningún tag conocido.
+pub fn field_frame_ids(name: &str) -> Result<Vec<String>, String> {
+    let ids: &[&str] = match name.trim().to_lowercase().as_str() {
+        "title" | "título" | "titulo" => &["TIT2"],
+        "artist" | "artista" => &["TPE1"],
+        "album" | "álbum" => &["TALB"],
+        "year" | "año" => &["TYER"],
+        "date" | "fecha" => &["TDRC"],
+        "genre" | "género" | "genero" => &["TCON"],
+        "track" | "pista" => &["TRCK"],
+        "season" | "temporada" | "disc" | "disco" => &["TPOS"],
+        "copyright" => &["TCOP"],
+        "composer" | "compositor" => &["TCOM"],
+        "subtitle" | "subtítulo" | "subtitulo" | "description" | "descripción" | "descripcion" => {
+            &["TIT3"]
+        }
+        "original_artist" | "original-artist" | "artista_original" | "artista-original" => {
+            &["TOPE"]
+        }
+        "album_artist" | "album-artist" | "artista_album" | "artista-album" => &["TPE2"],
+        "cover" | "carátula" | "caratula" => &["APIC"],
+        "lyrics" | "letra" => &["USLT"],
+        "synced_lyrics" | "synced-lyrics" | "letra_sincronizada" | "letra-sincronizada" => {
+            &["SYLT"]
+        }
+        "url" => &["WOAR"],
+        "podcast" => &["PCST"],
+        "category" | "categoría" | "categoria" => &["TCAT"],
+        "long_description" | "long-description" | "descripción_larga" | "descripcion_larga"
+        | "descripción-larga" | "descripcion-larga" => &["TDES"],
+        "keywords" | "palabras_clave" | "palabras-clave" => &["TKWD"],
+        "episode_id" | "episode-id" | "guid" => &["TGID"],
+        "feed_url" | "feed-url" | "feed" => &["WFED"],
+        "comment" | "comentario" => &["COMM"],
+        "compilation" | "compilación" | "compilacion" => &["TCMP"],
+        "album_sort" | "album-sort" | "orden_album" | "orden-album" => &["TSOA"],
+        "artist_sort" | "artist-sort" | "orden_artista" | "orden-artista" => &["TSOP"],
+        "title_sort" | "title-sort" | "orden_titulo" | "orden-titulo" => &["TSOT"],
+        "chapters" | "capítulos" | "capitulos" => &["CHAP", "CTOC"],
+        _ => {
+            return validate_frame_id(name).map(|id| vec![id]).map_err(|_| {
+                format!(
+                    "Tag desconocido: '{}'. Usa un nombre como title, album, cover, lyrics, chapters... o un ID de frame ID3v2.4",
+                    name
+                )
+            });
+        }
+    };
+    Ok(ids.iter().map(|id| id.to_string()).collect())
+}
+
+/// Copia los frames de un tag de origen a otro de destino
+///
+/// Con `only` vacío se copian todos los frames; si no, solo los de los tags
+/// indicados. Los tags de `except` se excluyen en ambos casos. Los frames del
+/// destino con el mismo ID que alguno copiado se sustituyen por completo, de
+/// modo que el destino queda igual que el origen para esos frames.
+///
+/// # Retorna
+///
+/// El número de frames copiados
+///
+/// # Errores
+///
+/// Retorna un error si algún nombre de `only` o `except` no es válido.
+pub fn copy_tags(
+    source: &Tag,
+    dest: &mut Tag,
+    only: &[String],
+    except: &[String],
+) -> Result<usize, String> {
+    let resolve = |names: &[String]| -> Result<Vec<String>, String> {
+        let mut ids = Vec::new();
+        for name in names {
+            ids.extend(field_frame_ids(name)?);
+        }
+        Ok(ids)
+    };
+    let only = resolve(only)?;
+    let except = resolve(except)?;
+
+    let frames: Vec<&Frame> = source
+        .frames()
+        .filter(|frame| only.is_empty() || only.iter().any(|id| id == frame.id()))
+        .filter(|frame| !except.iter().any(|id| id == frame.id()))
+        .collect();
+
+    let mut ids: Vec<&str> = frames.iter().map(|frame| frame.id()).collect();
+    ids.sort_unstable();
+    ids.dedup();
+    for id in ids {
+        dest.remove(id);
+    }
+    for frame in &frames {
+        dest.add_frame((*frame).clone());
     }
 
-    has_frames
+    Ok(frames.len())
 }
 
 /// Muestra todos los tags del archivo MP3 en formato legible
@@ -461,11 +1545,17 @@ pub fn display_tags(tag: &Tag) {
     }
 
     if let Some(track) = tag.track() {
-        println!("#️⃣  Pista:     {}", track);
+        println!(
+            "#️⃣  Pista:     {}",
+            number_with_total(track, tag.total_tracks())
+        );
     }
 
     if let Some(season) = tag.disc() {
-        println!("📺 Temporada: {}", season);
+        println!(
+            "📺 Temporada: {}",
+            number_with_total(season, tag.total_discs())
+        );
     }
 
     if let Some(copyright) = tag.get("TCOP").and_then(|f| f.content().text()) {
@@ -498,6 +1588,54 @@ pub fn display_tags(tag: &Tag) {
         }
     }
 
+    // Mostrar metadatos de podcast de iTunes
+    if tag.get("PCST").is_some() {
+        println!("🎙️  Podcast: Sí");
+    }
+    if let Some(category) = tag.get("TCAT").and_then(|f| f.content().text()) {
+        println!("🗂️  Categoría: {}", category);
+    }
+    if let Some(keywords) = tag.get("TKWD").and_then(|f| f.content().text()) {
+        println!("🔑 Palabras clave: {}", keywords);
+    }
+    if let Some(episode_id) = tag.get("TGID").and_then(|f| f.content().text()) {
+        println!("🆔 ID del episodio: {}", episode_id);
+    }
+    if let Some(feed) = tag.get("WFED").and_then(|f| f.content().link()) {
+        println!("📡 Feed: {}", feed);
+    }
+    if let Some(description) = tag.get("TDES").and_then(|f| f.content().text()) {
+        println!("📜 Descripción larga:");
+        // Mostrar solo las primeras 3 líneas como preview
+        let lines: Vec<&str> = description.lines().collect();
+        for line in lines.iter().take(3) {
+            println!("   {}", line);
+        }
+        if lines.len() > 3 {
+            println!("   ... ({} líneas más)", lines.len() - 3);
+        }
+    }
+
+    // Mostrar comentarios (COMM)
+    for comment in tag.comments() {
+        if comment.description.is_empty() {
+            println!("💬 Comentario [{}]: {}", comment.lang, comment.text);
+        } else {
+            println!(
+                "💬 Comentario [{}] {}: {}",
+                comment.lang, comment.description, comment.text
+            );
+        }
+    }
+
+    // Mostrar frames definidos por el usuario (TXXX / WXXX)
+    for text in tag.extended_texts() {
+        println!("🏷️  {}: {}", text.description, text.value);
+    }
+    for link in tag.extended_links() {
+        println!("🔗 {}: {}", link.description, link.link);
+    }
+
     let pictures: Vec<_> = tag.pictures().collect();
     if !pictures.is_empty() {
         println!("🖼️  Carátulas: {} imagen(es)", pictures.len());
@@ -509,22 +1647,44 @@ pub fn display_tags(tag: &Tag) {
                 pic.mime_type,
                 pic.data.len()
             );
+            if !pic.description.is_empty() {
+                println!("       Descripción: {}", pic.description);
+            }
         }
     }
 
-    // Mostrar lyrics si existen
-    for frame in tag.frames() {
-        if let Content::Lyrics(lyrics) = frame.content() {
+    // Mostrar lyrics si existen (una entrada por idioma y descripción)
+    for lyrics in tag.lyrics() {
+        if lyrics.description.is_empty() {
             println!("📝 Letra ({}):", lyrics.lang);
-            // Mostrar solo las primeras 3 líneas como preview
-            let lines: Vec<&str> = lyrics.text.lines().collect();
-            for line in lines.iter().take(3) {
-                println!("   {}", line);
-            }
-            if lines.len() > 3 {
-                println!("   ... ({} líneas más)", lines.len() - 3);
+        } else {
+            println!("📝 Letra ({}, {}):", lyrics.lang, lyrics.description);
+        }
+        // Mostrar solo las primeras 3 líneas como preview
+        let lines: Vec<&str> = lyrics.text.lines().collect();
+        for line in lines.iter().take(3) {
+            println!("   {}", line);
+        }
+        if lines.len() > 3 {
+            println!("   ... ({} líneas más)", lines.len() - 3);
+        }
+    }
+
+    // Mostrar letras sincronizadas (SYLT)
+    for sylt in tag.synchronised_lyrics() {
+        println!(
+            "🎤 Letra sincronizada ({}): {} línea(s)",
+            sylt.lang,
+            sylt.content.len()
+        );
+        for (time, line) in sylt.content.iter().take(3) {
+            match sylt.timestamp_format {
+                TimestampFormat::Ms => println!("   [{}] {}", format_lrc_time(*time), line),
+                TimestampFormat::Mpeg => println!("   [frame {}] {}", time, line),
             }
-            break; // Solo mostrar el primer frame de lyrics
+        }
+        if sylt.content.len() > 3 {
+            println!("   ... ({} líneas más)", sylt.content.len() - 3);
         }
     }
 
@@ -556,5 +1716,2509 @@ pub fn display_tags(tag: &Tag) {
     println!("═══════════════════════════════════════\n");
 }
 
+/// Representación serializable de un tag ID3 completo
+///
+/// Es la estructura que emite `id3cli show --format json`. Los campos son
+/// estables para que puedan consumirse desde scripts.
+#[derive(Debug, Default, Serialize)]
+pub struct TagReport {
+    /// Versión del tag (`ID3v2.3`, `ID3v2.4`...) o `null` si no hay tag
+    pub version: Option<String>,
+    /// Tamaño en bytes del tag en el archivo según su cabecera, o `null` si
+    /// no se conoce (ver [`read_tag_size`])
+    pub size: Option<u32>,
+    /// Todos los frames del tag, en el orden en que aparecen
+    pub frames: Vec<FrameReport>,
+}
+
+/// Representación serializable de un frame ID3
+#[derive(Debug, Serialize)]
+pub struct FrameReport {
+    /// Identificador de cuatro letras del frame (TIT2, APIC, USLT...)
+    pub id: String,
+    /// Nombre descriptivo del frame según la especificación ID3
+    pub name: String,
+    /// Contenido decodificado del frame
+    pub content: FrameContent,
+}
+
+/// Contenido decodificado de un frame ID3
+///
+/// Se serializa con un campo `type` que indica la variante.
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum FrameContent {
+    /// Frame de texto (T***)
+    Text { text: String },
+    /// Frame de texto definido por el usuario (TXXX)
+    ExtendedText { description: String, value: String },
+    /// Frame de enlace (W***)
+    Link { url: String },
+    /// Frame de enlace definido por el usuario (WXXX)
+    ExtendedLink { description: String, url: String },
+    /// Comentario (COMM)
+    Comment {
+        lang: String,
+        description: String,
+        text: String,
+    },
+    /// Letra no sincronizada (USLT)
+    Lyrics {
+        lang: String,
+        description: String,
+        text: String,
+    },
+    /// Letra sincronizada (SYLT); los tiempos están en la unidad de `timestamp_format`
+    SynchronisedLyrics {
+        lang: String,
+        description: String,
+        timestamp_format: String,
+        content_type: String,
+        lines: Vec<(u32, String)>,
+    },
+    /// Imagen (APIC); solo se incluyen los metadatos, no los datos binarios
+    Picture {
+        mime_type: String,
+        picture_type: String,
+        description: String,
+        size: usize,
+    },
+    /// Capítulo (CHAP) con sus subframes
+    Chapter {
+        element_id: String,
+        start_time: u32,
+        end_time: u32,
+        frames: Vec<FrameReport>,
+    },
+    /// Marca de podcast de iTunes (PCST)
+    Podcast { podcast: bool },
+    /// Tabla de contenidos (CTOC) con sus subframes
+    TableOfContents {
+        element_id: String,
+        top_level: bool,
+        ordered: bool,
+        elements: Vec<String>,
+        frames: Vec<FrameReport>,
+    },
+    /// Cualquier otro frame, representado por su forma textual
+    Other { value: String },
+}
+
+impl From<&Frame> for FrameReport {
+    fn from(frame: &Frame) -> Self {
+        let content = match frame.content() {
+            Content::Text(text) => FrameContent::Text { text: text.clone() },
+            Content::ExtendedText(ext) => FrameContent::ExtendedText {
+                description: ext.description.clone(),
+                value: ext.value.clone(),
+            },
+            Content::Link(url) => FrameContent::Link { url: url.clone() },
+            Content::ExtendedLink(ext) => FrameContent::ExtendedLink {
+                description: ext.description.clone(),
+                url: ext.link.clone(),
+            },
+            Content::Comment(comment) => FrameContent::Comment {
+                lang: comment.lang.clone(),
+                description: comment.description.clone(),
+                text: comment.text.clone(),
+            },
+            Content::Lyrics(lyrics) => FrameContent::Lyrics {
+                lang: lyrics.lang.clone(),
+                description: lyrics.description.clone(),
+                text: lyrics.text.clone(),
+            },
+            Content::SynchronisedLyrics(sylt) => FrameContent::SynchronisedLyrics {
+                lang: sylt.lang.clone(),
+                description: sylt.description.clone(),
+                timestamp_format: sylt.timestamp_format.to_string(),
+                content_type: sylt.content_type.to_string(),
+                lines: sylt.content.clone(),
+            },
+            Content::Picture(pic) => FrameContent::Picture {
+                mime_type: pic.mime_type.clone(),
+                picture_type: pic.picture_type.to_string(),
+                description: pic.description.clone(),
+                size: pic.data.len(),
+            },
+            Content::Chapter(chapter) => FrameContent::Chapter {
+                element_id: chapter.element_id.clone(),
+                start_time: chapter.start_time,
+                end_time: chapter.end_time,
+                frames: chapter.frames.iter().map(FrameReport::from).collect(),
+            },
+            Content::TableOfContents(toc) => FrameContent::TableOfContents {
+                element_id: toc.element_id.clone(),
+                top_level: toc.top_level,
+                ordered: toc.ordered,
+                elements: toc.elements.clone(),
+                frames: toc.frames.iter().map(FrameReport::from).collect(),
+            },
+            // PCST es un entero de 4 bytes; cualquier valor distinto de cero lo activa
+            Content::Unknown(unknown) if frame.id() == "PCST" => FrameContent::Podcast {
+                podcast: unknown.data.iter().any(|&byte| byte != 0),
+            },
+            other => FrameContent::Other {
+                value: other.to_string(),
+            },
+        };
+
+        FrameReport {
+            id: frame.id().to_string(),
+            name: frame.name().to_string(),
+            content,
+        }
+    }
+}
+
+/// Construye una representación serializable de todos los frames del tag
+///
+/// A diferencia de `display_tags`, no imprime nada: devuelve un `TagReport`
+/// que se puede convertir a JSON con `serde_json`. `size` es el tamaño del
+/// tag en el archivo (ver [`read_tag_size`]).
+pub fn tag_report(tag: &Tag, size: Option<u32>) -> TagReport {
+    TagReport {
+        version: Some(tag.version().to_string()),
+        size,
+        frames: tag.frames().map(FrameReport::from).collect(),
+    }
+}
+
+/// Lee el tamaño del tag ID3v2 desde la cabecera del archivo
+///
+/// Incluye la cabecera de 10 bytes y, si existe, el pie de ID3v2.4.
+///
+/// # Retorna
+///
+/// El tamaño en bytes, o `None` si el archivo no empieza por una cabecera
+/// ID3v2 válida
+pub fn read_tag_size(path: &Path) -> Option<u32> {
+    let mut header = [0u8; 10];
+    fs::File::open(path).ok()?.read_exact(&mut header).ok()?;
+    if &header[..3] != b"ID3" || header[6..].iter().any(|&byte| byte & 0x80 != 0) {
+        return None;
+    }
+
+    // El tamaño es un entero "synchsafe": 7 bits útiles por byte
+    let size = header[6..]
+        .iter()
+        .fold(0u32, |size, &byte| (size << 7) | u32::from(byte));
+    let footer = if header[3] == 4 && header[5] & 0x10 != 0 {
+        10
+    } else {
+        0
+    };
+    Some(10 + size + footer)
+}
+
+/// Cambio en un frame entre dos versiones de un tag
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FrameChange {
+    /// Frame que no existía y se añadiría
+    Added { id: String, value: String },
+    /// Frame existente cuyo contenido cambiaría
+    Changed {
+        id: String,
+        old: String,
+        new: String,
+    },
+    /// Frame existente que se eliminaría
+    Removed { id: String, value: String },
+}
+
+/// Clave que identifica un frame dentro de un tag
+///
+/// Dos frames con la misma clave se consideran "el mismo frame" y se comparan
+/// por contenido. Sigue las reglas de unicidad de ID3v2.4: descripción en
+/// TXXX/WXXX, idioma y descripción en COMM/USLT, tipo de imagen en APIC, etc.
+/// En SYLT se usan idioma y tipo de contenido, como hace `Tag::add_frame`.
+fn frame_key(frame: &Frame) -> String {
+    let discriminator = match frame.content() {
+        Content::ExtendedText(ext) => ext.description.clone(),
+        Content::ExtendedLink(ext) => ext.description.clone(),
+        Content::Comment(comment) => format!("{}/{}", comment.lang, comment.description),
+        Content::Lyrics(lyrics) => format!("{}/{}", lyrics.lang, lyrics.description),
+        Content::SynchronisedLyrics(sylt) => format!("{}/{}", sylt.lang, sylt.content_type),
+        Content::Picture(pic) => pic.picture_type.to_string(),
+        Content::Chapter(chapter) => chapter.element_id.clone(),
+        Content::TableOfContents(toc) => toc.element_id.clone(),
+        // WOAR y WCOM pueden repetirse con distinta URL
+        Content::Link(url) if frame.id() == "WOAR" || frame.id() == "WCOM" => url.clone(),
+        _ => String::new(),
+    };
+    format!("{}:{}", frame.id(), discriminator)
+}
+
+/// Resumen de una línea del contenido de un frame, para mostrar en un diff
+pub fn frame_summary(frame: &Frame) -> String {
+    fn multiline(prefix: String, text: &str) -> String {
+        let lines: Vec<&str> = text.lines().collect();
+        match lines.len() {
+            0 | 1 => format!("{}{}", prefix, text),
+            n => format!("{}{}… ({} líneas)", prefix, lines[0], n),
+        }
+    }
+
+    match frame.content() {
+        Content::Picture(pic) => format!(
+            "{:?}, {}, {} bytes",
+            pic.picture_type,
+            pic.mime_type,
+            pic.data.len()
+        ),
+        Content::Lyrics(lyrics) => multiline(format!("[{}] ", lyrics.lang), &lyrics.text),
+        Content::Comment(comment) => multiline(format!("[{}] ", comment.lang), &comment.text),
+        Content::ExtendedText(ext) => format!("{}={}", ext.description, ext.value),
+        Content::ExtendedLink(ext) => format!("{}={}", ext.description, ext.link),
+        other => multiline(String::new(), &other.to_string()),
+    }
+}
+
+/// Calcula las diferencias frame a frame entre dos tags
+///
+/// Los cambios se devuelven en el orden de los frames de `after` (añadidos y
+/// modificados), seguidos de los frames eliminados en el orden de `before`.
+pub fn diff_tags(before: &Tag, after: &Tag) -> Vec<FrameChange> {
+    let mut old_frames: Vec<(String, &Frame)> =
+        before.frames().map(|f| (frame_key(f), f)).collect();
+    let mut changes = Vec::new();
+
+    for frame in after.frames() {
+        let key = frame_key(frame);
+        match old_frames.iter().position(|(k, _)| *k == key) {
+            Some(index) => {
+                let (_, old) = old_frames.remove(index);
+                if old.content() != frame.content() {
+                    changes.push(FrameChange::Changed {
+                        id: frame.id().to_string(),
+                        old: frame_summary(old),
+                        new: frame_summary(frame),
+                    });
+                }
+            }
+            None => changes.push(FrameChange::Added {
+                id: frame.id().to_string(),
+                value: frame_summary(frame),
+            }),
+        }
+    }
+
+    for (_, frame) in old_frames {
+        changes.push(FrameChange::Removed {
+            id: frame.id().to_string(),
+            value: frame_summary(frame),
+        });
+    }
+
+    changes
+}
+
+/// Muestra un diff de frames en formato legible
+///
+/// Cada línea empieza por `+` (añadido), `~` (modificado) o `-` (eliminado).
+pub fn display_diff(changes: &[FrameChange]) {
+    if changes.is_empty() {
+        println!("   (sin cambios)");
+        return;
+    }
+
+    for change in changes {
+        match change {
+            FrameChange::Added { id, value } => println!("   + {}: {}", id, value),
+            FrameChange::Changed { id, old, new } => println!("   ~ {}: {} → {}", id, old, new),
+            FrameChange::Removed { id, value } => println!("   - {}: {}", id, value),
+        }
+    }
+}
+
+/// Todos los tipos de imagen APIC, en el orden de su código numérico ID3 (0-20)
+const PICTURE_TYPES: [PictureType; 21] = [
+    PictureType::Other,
+    PictureType::Icon,
+    PictureType::OtherIcon,
+    PictureType::CoverFront,
+    PictureType::CoverBack,
+    PictureType::Leaflet,
+    PictureType::Media,
+    PictureType::LeadArtist,
+    PictureType::Artist,
+    PictureType::Conductor,
+    PictureType::Band,
+    PictureType::Composer,
+    PictureType::Lyricist,
+    PictureType::RecordingLocation,
+    PictureType::DuringRecording,
+    PictureType::DuringPerformance,
+    PictureType::ScreenCapture,
+    PictureType::BrightFish,
+    PictureType::Illustration,
+    PictureType::BandLogo,
+    PictureType::PublisherLogo,
+];
+
+/// Convierte un nombre o código de tipo de imagen en `PictureType`
+///
+/// Acepta el nombre de la variante sin distinguir mayúsculas ni separadores
+/// (`CoverFront`, `cover_front`, `cover-front`), las abreviaturas `front` y
+/// `back` o su código numérico ID3 (`3`).
+pub fn parse_picture_type(name: &str) -> Option<PictureType> {
+    if let Ok(code) = name.trim().parse::<u8>() {
+        return Some(
+            PICTURE_TYPES
+                .get(code as usize)
+                .copied()
+                .unwrap_or(PictureType::Undefined(code)),
+        );
+    }
+
+    let normalized: String = name
+        .chars()
+        .filter(|c| !matches!(c, '_' | '-' | ' '))
+        .collect::<String>()
+        .to_lowercase();
+    match normalized.as_str() {
+        "front" => return Some(PictureType::CoverFront),
+        "back" => return Some(PictureType::CoverBack),
+        _ => {}
+    }
+    PICTURE_TYPES
+        .iter()
+        .copied()
+        .find(|pt| format!("{:?}", pt).to_lowercase() == normalized)
+}
+
+/// Nombre de un tipo de imagen tal como lo acepta `parse_picture_type`
+pub fn picture_type_name(picture_type: PictureType) -> String {
+    match picture_type {
+        PictureType::Undefined(code) => code.to_string(),
+        other => format!("{:?}", other),
+    }
+}
+
+/// Selección de carátulas por tipo
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PictureSelector {
+    /// Todas las carátulas
+    All,
+    /// Solo las del tipo indicado
+    Type(PictureType),
+}
+
+/// Valida un tipo de imagen aceptado por [`parse_picture_type`]
+///
+/// # Errores
+///
+/// Retorna un error con los tipos más habituales si el nombre no es válido.
+pub fn validate_picture_type(name: &str) -> Result<PictureType, String> {
+    parse_picture_type(name).ok_or_else(|| {
+        format!(
+            "Tipo de imagen no válido: '{}' (usa front, back, artist, leaflet, media, band-logo...)",
+            name
+        )
+    })
+}
+
+/// Interpreta un selector de carátulas: `all` o un tipo de imagen aceptado
+/// por [`parse_picture_type`]
+pub fn parse_picture_selector(name: &str) -> Result<PictureSelector, String> {
+    if name.trim().eq_ignore_ascii_case("all") {
+        return Ok(PictureSelector::All);
+    }
+    validate_picture_type(name).map(PictureSelector::Type)
+}
+
+/// Nombre de un tipo de imagen en minúsculas separado por guiones
+/// (`CoverFront` → `cover-front`), apto para nombres de archivo
+fn picture_type_slug(picture_type: PictureType) -> String {
+    let name = picture_type_name(picture_type);
+    let mut slug = String::new();
+    for (i, c) in name.chars().enumerate() {
+        if c.is_ascii_uppercase() && i > 0 {
+            slug.push('-');
+        }
+        slug.push(c.to_ascii_lowercase());
+    }
+    slug
+}
+
+/// Extrae las carátulas del tag a archivos de imagen
+///
+/// La extensión se deduce del contenido de cada carátula (ver
+/// [`sniff_mime_type`]) o, si no se reconoce, de su tipo MIME. Si se extrae
+/// una sola carátula y `output` no es un directorio existente, se escribe en
+/// `output`, cuya extensión se añade o se corrige si no corresponde a la
+/// imagen. En otro caso se escriben en el directorio `output` (o junto al
+/// MP3) como `<nombre>-<tipo>.<ext>`.
+///
+/// Los archivos existentes solo se sobrescriben con `force`.
+///
+/// # Errores
+///
+/// Retorna un error, sin escribir nada, si no hay carátulas del tipo
+/// seleccionado o algún archivo ya existe y no se indica `force`; también si
+/// no se puede escribir algún archivo.
+///
+/// # Retorna
+///
+/// Las rutas de los archivos escritos
+pub fn extract_pictures(
+    tag: &Tag,
+    file: &Path,
+    selector: PictureSelector,
+    output: Option<&Path>,
+    force: bool,
+) -> Result<Vec<PathBuf>, Box<dyn std::error::Error>> {
+    let pictures: Vec<&Picture> = tag
+        .pictures()
+        .filter(|p| match selector {
+            PictureSelector::All => true,
+            PictureSelector::Type(picture_type) => p.picture_type == picture_type,
+        })
+        .collect();
+
+    if pictures.is_empty() {
+        return Err(match selector {
+            PictureSelector::All => format!("'{}' no tiene carátulas", file.display()),
+            PictureSelector::Type(picture_type) => format!(
+                "'{}' no tiene carátulas de tipo {}",
+                file.display(),
+                picture_type_name(picture_type)
+            ),
+        }
+        .into());
+    }
+
+    let mut paths = Vec::new();
+    for (i, picture) in pictures.iter().enumerate() {
+        let mime_type = sniff_mime_type(&picture.data).unwrap_or(&picture.mime_type);
+        let extension = mime_extension(mime_type);
+        let path = match output {
+            Some(output) if pictures.len() == 1 && !output.is_dir() => {
+                if detect_mime_type(output).is_ok_and(|expected| expected == mime_type) {
+                    output.to_path_buf()
+                } else {
+                    output.with_extension(extension)
+                }
+            }
+            _ => {
+                let dir = output
+                    .map(Path::to_path_buf)
+                    .or_else(|| file.parent().map(Path::to_path_buf))
+                    .unwrap_or_default();
+                let stem = file
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy().into_owned())
+                    .unwrap_or_else(|| "cover".to_string());
+                let slug = picture_type_slug(picture.picture_type);
+                // Puede haber varias imágenes del mismo tipo en tags escritos por otras herramientas
+                let repeated = pictures[..i]
+                    .iter()
+                    .filter(|p| p.picture_type == picture.picture_type)
+                    .count();
+                let name = if repeated == 0 {
+                    format!("{}-{}.{}", stem, slug, extension)
+                } else {
+                    format!("{}-{}-{}.{}", stem, slug, repeated + 1, extension)
+                };
+                dir.join(name)
+            }
+        };
+        paths.push(path);
+    }
+
+    if !force && let Some(existing) = paths.iter().find(|path| path.exists()) {
+        return Err(format!(
+            "'{}' ya existe (usa --force para sobrescribirlo)",
+            existing.display()
+        )
+        .into());
+    }
+
+    for (path, picture) in paths.iter().zip(&pictures) {
+        if let Some(parent) = path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, &picture.data)?;
+    }
+
+    Ok(paths)
+}
+
+/// Extensión de archivo habitual para un tipo MIME de imagen
+///
+/// Retorna `bin` si el tipo MIME no es una imagen conocida.
+pub fn mime_extension(mime_type: &str) -> &'static str {
+    match mime_type.to_lowercase().as_str() {
+        "image/jpeg" | "image/jpg" => "jpg",
+        "image/png" => "png",
+        "image/webp" => "webp",
+        "image/gif" => "gif",
+        "image/bmp" => "bmp",
+        _ => "bin",
+    }
+}
+
+/// Metadatos de un archivo MP3 en formato sidecar (JSON)
+///
+/// Contiene todos los frames que gestiona la CLI (metadatos, totales,
+/// comentarios, letras, frames de usuario, podcast, capítulos y carátulas),
+/// de forma que el tag se puede reconstruir con `import_sidecar`. Los demás
+/// frames de texto o URL (los de `--frame`) van en `frames` por su ID. Los
+/// campos ausentes no modifican el tag al importar.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TagSidecar {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub artist: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub album: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub year: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub genre: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub track: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub track_total: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub season: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub season_total: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub date: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub copyright: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub composer: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subtitle: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub original_artist: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub album_artist: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub comments: Vec<SidecarLyrics>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub lyrics: Vec<SidecarLyrics>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub synced_lyrics: Vec<SidecarSyncedLyrics>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub urls: Vec<String>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub txxx: BTreeMap<String, String>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub wxxx: BTreeMap<String, String>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub compilation: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub album_sort: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub artist_sort: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title_sort: Option<String>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub podcast: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub long_description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub keywords: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub episode_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub feed_url: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub chapters: Vec<SidecarChapter>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub frames: BTreeMap<String, String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub pictures: Vec<SidecarPicture>,
+}
+
+/// Frames de texto y URL que tienen un campo propio en [`TagSidecar`] y no se
+/// repiten en `frames`
+const SIDECAR_FIELD_FRAMES: &[&str] = &[
+    "TIT2", "TPE1", "TALB", "TYER", "TCON", "TRCK", "TPOS", "TDRC", "TCOP", "TCOM", "TIT3", "TOPE",
+    "TPE2", "WOAR", "TCMP", "TSOA", "TSOP", "TSOT", "TCAT", "TDES", "TKWD", "TGID", "WFED",
+];
+
+/// Letra (USLT) o comentario (COMM) dentro de un sidecar
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SidecarLyrics {
+    pub lang: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub description: String,
+    pub text: String,
+}
+
+/// Letra sincronizada (SYLT) dentro de un sidecar
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SidecarSyncedLyrics {
+    pub lang: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub description: String,
+    pub lines: Vec<SidecarSyncedLine>,
+}
+
+/// Línea de una letra sincronizada; `time` en milisegundos
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SidecarSyncedLine {
+    pub time: u32,
+    pub text: String,
+}
+
+/// Capítulo (CHAP) dentro de un sidecar
+///
+/// `start` y `end` usan el formato `HH:MM:SS.mmm`. La tabla de contenidos
+/// (CTOC) se regenera al importar a partir de los capítulos.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SidecarChapter {
+    pub start: String,
+    pub end: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub title: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image: Option<SidecarPicture>,
+}
+
+/// Imagen (APIC) dentro de un sidecar
+///
+/// La imagen se indica con `data` (contenido en base64) o con `file` (ruta
+/// a un archivo externo, relativa al directorio del sidecar). Sin
+/// `mime_type`, el tipo se detecta por el contenido de la imagen.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SidecarPicture {
+    pub picture_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub description: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file: Option<PathBuf>,
+}
+
+impl TagSidecar {
+    /// Serializa el sidecar a JSON
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string_pretty(self).map_err(|e| e.to_string())
+    }
+
+    /// Lee un sidecar desde texto JSON
+    pub fn from_json(text: &str) -> Result<Self, String> {
+        serde_json::from_str(text).map_err(|e| e.to_string())
+    }
+}
+
+/// Obtiene el texto de un frame como `String`
+fn frame_text(tag: &Tag, id: &str) -> Option<String> {
+    tag.get(id)
+        .and_then(|f| f.content().text())
+        .map(str::to_string)
+}
+
+/// Exporta el tag a un sidecar con las carátulas incrustadas en base64
+pub fn export_sidecar(tag: &Tag) -> TagSidecar {
+    TagSidecar {
+        title: tag.title().map(str::to_string),
+        artist: tag.artist().map(str::to_string),
+        album: tag.album().map(str::to_string),
+        year: tag.year(),
+        genre: tag.genre().map(str::to_string),
+        track: tag.track(),
+        track_total: tag.total_tracks(),
+        season: tag.disc(),
+        season_total: tag.total_discs(),
+        date: tag.date_recorded().map(|d| d.to_string()),
+        copyright: frame_text(tag, "TCOP"),
+        composer: frame_text(tag, "TCOM"),
+        subtitle: frame_text(tag, "TIT3"),
+        original_artist: frame_text(tag, "TOPE"),
+        album_artist: tag.album_artist().map(str::to_string),
+        comments: tag
+            .comments()
+            .map(|c| SidecarLyrics {
+                lang: c.lang.clone(),
+                description: c.description.clone(),
+                text: c.text.clone(),
+            })
+            .collect(),
+        lyrics: tag
+            .lyrics()
+            .map(|l| SidecarLyrics {
+                lang: l.lang.clone(),
+                description: l.description.clone(),
+                text: l.text.clone(),
+            })
+            .collect(),
+        // Las marcas en frames MPEG no se pueden expresar en milisegundos
+        synced_lyrics: tag
+            .synchronised_lyrics()
+            .filter(|s| s.timestamp_format == TimestampFormat::Ms)
+            .map(|s| SidecarSyncedLyrics {
+                lang: s.lang.clone(),
+                description: s.description.clone(),
+                lines: s
+                    .content
+                    .iter()
+                    .map(|(time, text)| SidecarSyncedLine {
+                        time: *time,
+                        text: text.clone(),
+                    })
+                    .collect(),
+            })
+            .collect(),
+        urls: tag
+            .frames()
+            .filter(|f| f.id() == "WOAR")
+            .filter_map(|f| f.content().link())
+            .map(str::to_string)
+            .collect(),
+        txxx: tag
+            .extended_texts()
+            .map(|t| (t.description.clone(), t.value.clone()))
+            .collect(),
+        wxxx: tag
+            .extended_links()
+            .map(|l| (l.description.clone(), l.link.clone()))
+            .collect(),
+        compilation: frame_text(tag, "TCMP").as_deref() == Some("1"),
+        album_sort: frame_text(tag, "TSOA"),
+        artist_sort: frame_text(tag, "TSOP"),
+        title_sort: frame_text(tag, "TSOT"),
+        podcast: tag.get("PCST").is_some(),
+        category: frame_text(tag, "TCAT"),
+        long_description: frame_text(tag, "TDES"),
+        keywords: frame_text(tag, "TKWD"),
+        episode_id: frame_text(tag, "TGID"),
+        feed_url: tag
+            .get("WFED")
+            .and_then(|f| f.content().link())
+            .map(str::to_string),
+        chapters: sorted_chapters(tag)
+            .into_iter()
+            .map(export_chapter)
+            .collect(),
+        frames: tag
+            .frames()
+            .filter(|f| !SIDECAR_FIELD_FRAMES.contains(&f.id()))
+            .filter_map(|f| {
+                let value = match f.content() {
+                    Content::Text(text) => text,
+                    Content::Link(url) => url,
+                    _ => return None,
+                };
+                // Solo los que `--frame` (y por tanto la importación) admite
+                validate_editable_frame(f.id(), value).ok()?;
+                Some((f.id().to_string(), value.clone()))
+            })
+            .collect(),
+        pictures: tag.pictures().map(export_picture).collect(),
+    }
+}
+
+/// Convierte una imagen del tag a su forma en el sidecar (base64)
+fn export_picture(picture: &Picture) -> SidecarPicture {
+    SidecarPicture {
+        picture_type: picture_type_name(picture.picture_type),
+        mime_type: Some(picture.mime_type.clone()),
+        description: picture.description.clone(),
+        data: Some(BASE64.encode(&picture.data)),
+        file: None,
+    }
+}
+
+/// Convierte un capítulo del tag a su forma en el sidecar
+fn export_chapter(chapter: &Chapter) -> SidecarChapter {
+    let frame = |id: &str| chapter.frames.iter().find(|f| f.id() == id);
+    SidecarChapter {
+        start: format_chapter_time(chapter.start_time),
+        end: format_chapter_time(chapter.end_time),
+        title: frame("TIT2")
+            .and_then(|f| f.content().text())
+            .unwrap_or_default()
+            .to_string(),
+        url: frame("WXXX")
+            .and_then(|f| f.content().extended_link())
+            .map(|l| l.link.clone()),
+        image: frame("APIC")
+            .and_then(|f| f.content().picture())
+            .map(export_picture),
+    }
+}
+
+/// Guarda las carátulas incrustadas del sidecar como archivos en `dir`
+///
+/// Cada imagen se escribe como `{prefix}-{n}.{ext}` y su campo `data` se
+/// sustituye por `file` con la ruta `dir/{prefix}-{n}.{ext}`.
+///
+/// # Errores
+///
+/// Retorna un error si el base64 no es válido o no se puede escribir el archivo
+pub fn externalize_pictures(
+    sidecar: &mut TagSidecar,
+    dir: &Path,
+    prefix: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    fs::create_dir_all(dir)?;
+
+    let chapter_images = sidecar
+        .chapters
+        .iter_mut()
+        .enumerate()
+        .filter_map(|(i, chapter)| Some((format!("{}-chp{}", prefix, i), chapter.image.as_mut()?)));
+    let pictures = sidecar
+        .pictures
+        .iter_mut()
+        .enumerate()
+        .map(|(i, picture)| (format!("{}-{}", prefix, i + 1), picture));
+
+    for (name, picture) in pictures.chain(chapter_images) {
+        let Some(data) = picture.data.take() else {
+            continue;
+        };
+        let extension = mime_extension(picture.mime_type.as_deref().unwrap_or_default());
+        let path = dir.join(format!("{}.{}", name, extension));
+        fs::write(&path, BASE64.decode(data)?)?;
+        picture.file = Some(path);
+    }
+
+    Ok(())
+}
+
+/// Lee una imagen del sidecar, incrustada (`data`) o externa (`file`,
+/// relativa a `base_dir`)
+fn import_picture(
+    picture: &SidecarPicture,
+    base_dir: &Path,
+) -> Result<Picture, Box<dyn std::error::Error>> {
+    let picture_type = parse_picture_type(&picture.picture_type)
+        .ok_or_else(|| format!("Tipo de imagen no válido: '{}'", picture.picture_type))?;
+
+    let (data, mime_type) = match (&picture.data, &picture.file) {
+        (Some(data), _) => {
+            let data = BASE64.decode(data)?;
+            let mime_type = match &picture.mime_type {
+                Some(mime_type) => mime_type.clone(),
+                None => sniff_mime_type(&data)
+                    .ok_or(
+                        "No se reconoce el formato de una carátula en base64; indica 'mime_type'",
+                    )?
+                    .to_string(),
+            };
+            (data, mime_type)
+        }
+        (None, Some(file)) => {
+            let path = base_dir.join(file);
+            let data = fs::read(&path)
+                .map_err(|e| format!("No se pudo leer la carátula '{}': {}", path.display(), e))?;
+            let mime_type = match &picture.mime_type {
+                Some(mime_type) => mime_type.clone(),
+                None => image_mime_type(&path, &data)?.to_string(),
+            };
+            (data, mime_type)
+        }
+        (None, None) => {
+            return Err("Cada carátula debe indicar 'data' o 'file'".into());
+        }
+    };
+
+    Ok(Picture {
+        mime_type,
+        picture_type,
+        description: picture.description.clone(),
+        data,
+    })
+}
+
+/// Convierte un capítulo del sidecar en un frame CHAP
+fn import_chapter(
+    index: usize,
+    chapter: &SidecarChapter,
+    base_dir: &Path,
+) -> Result<Chapter, Box<dyn std::error::Error>> {
+    let mut frames = vec![Frame::text("TIT2", chapter.title.clone())];
+    if let Some(url) = &chapter.url {
+        frames.push(Frame::with_content(
+            "WXXX",
+            Content::ExtendedLink(ExtendedLink {
+                description: String::new(),
+                link: url.clone(),
+            }),
+        ));
+    }
+    if let Some(image) = &chapter.image {
+        frames.push(Frame::with_content(
+            "APIC",
+            Content::Picture(import_picture(image, base_dir)?),
+        ));
+    }
+
+    Ok(Chapter {
+        // Identificador provisional: rebuild_chapters renumera todos
+        element_id: format!("new{}", index),
+        start_time: parse_chapter_time(&chapter.start)?,
+        end_time: parse_chapter_time(&chapter.end)?,
+        start_offset: u32::MAX,
+        end_offset: u32::MAX,
+        frames,
+    })
+}
+
+/// Importa un sidecar al tag
+///
+/// Los campos presentes sobrescriben los del tag; los comentarios, letras,
+/// URLs, frames de usuario y carátulas se añaden (reemplazando los que
+/// tengan la misma clave). Si el sidecar tiene capítulos, sustituyen a los
+/// del tag. Las rutas relativas de carátulas externas se resuelven desde
+/// `base_dir`.
+///
+/// # Errores
+///
+/// Retorna un error si:
+/// * La fecha o una marca de tiempo de capítulo no tienen un formato válido
+/// * Un total no tiene número o es menor que él
+/// * Un frame de `frames` no se puede editar con `--frame` (ver [`set_raw_frame`])
+/// * Un tipo de imagen no es válido
+/// * Una carátula no tiene `data` ni `file`, el base64 no es válido o el archivo no se puede leer
+/// * Una carátula sin `mime_type` no tiene un formato de imagen reconocible
+///
+/// # Retorna
+///
+/// `true` si se aplicó al menos un cambio
+pub fn import_sidecar(
+    tag: &mut Tag,
+    sidecar: &TagSidecar,
+    base_dir: &Path,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    if let Some(date) = &sidecar.date {
+        date.parse::<id3::Timestamp>()
+            .map_err(|_| format!("Fecha no válida: '{}'", date))?;
+    }
+    let chapters = sidecar
+        .chapters
+        .iter()
+        .enumerate()
+        .map(|(index, chapter)| import_chapter(index, chapter, base_dir))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut changed = false;
+    for (id, value) in &sidecar.frames {
+        set_raw_frame(tag, id, value)?;
+        changed = true;
+    }
+
+    let artists: Vec<String> = sidecar.artist.iter().cloned().collect();
+    changed |= apply_metadata(
+        tag,
+        sidecar.title.as_deref(),
+        &artists,
+        sidecar.album.as_deref(),
+        sidecar.year,
+        sidecar.genre.as_deref(),
+        sidecar.track,
+        sidecar.season,
+        sidecar.date.as_deref(),
+        sidecar.copyright.as_deref(),
+        sidecar.composer.as_deref(),
+        sidecar.subtitle.as_deref(),
+        sidecar.original_artist.as_deref(),
+        sidecar.album_artist.as_deref(),
+    );
+    changed |= apply_totals(
+        tag,
+        sidecar.track,
+        sidecar.track_total,
+        sidecar.season,
+        sidecar.season_total,
+    )?;
+
+    for comment in &sidecar.comments {
+        changed |= add_comment(tag, &comment.text, &comment.lang, &comment.description);
+    }
+
+    for lyrics in &sidecar.lyrics {
+        changed |= add_lyrics(tag, &lyrics.text, &lyrics.lang, &lyrics.description);
+    }
+
+    for synced in &sidecar.synced_lyrics {
+        let lines: Vec<(u32, String)> = synced
+            .lines
+            .iter()
+            .map(|line| (line.time, line.text.clone()))
+            .collect();
+        changed |= add_synced_lyrics(tag, &lines, &synced.lang, &synced.description);
+    }
+
+    for url in &sidecar.urls {
+        changed |= add_url(tag, url);
+    }
+
+    for (description, value) in &sidecar.txxx {
+        changed |= add_user_text(tag, description, value);
+    }
+
+    for (description, url) in &sidecar.wxxx {
+        changed |= add_user_url(tag, description, url);
+    }
+
+    changed |= add_apple_metadata(
+        tag,
+        sidecar.compilation,
+        sidecar.album_sort.as_deref(),
+        sidecar.artist_sort.as_deref(),
+        sidecar.title_sort.as_deref(),
+    );
+
+    changed |= add_podcast_metadata(
+        tag,
+        &PodcastMetadata {
+            podcast: sidecar.podcast,
+            category: sidecar.category.as_deref(),
+            description: sidecar.long_description.as_deref(),
+            keywords: sidecar.keywords.as_deref(),
+            episode_id: sidecar.episode_id.as_deref(),
+            feed_url: sidecar.feed_url.as_deref(),
+        },
+    );
+
+    if !chapters.is_empty() {
+        remove_all_chapters(tag);
+        for chapter in chapters {
+            tag.add_frame(chapter);
+        }
+        rebuild_chapters(tag);
+        changed = true;
+    }
+
+    for picture in &sidecar.pictures {
+        tag.add_frame(import_picture(picture, base_dir)?);
+        changed = true;
+    }
+
+    Ok(changed)
+}
+
+/// Campos disponibles en las plantillas de `render_template`
+pub const TEMPLATE_FIELDS: &[&str] = &[
+    "title",
+    "artist",
+    "album",
+    "album_artist",
+    "track",
+    "disc",
+    "season",
+    "year",
+    "genre",
+    "date",
+    "composer",
+];
+
+/// Valor de un campo de plantilla leído del tag
+enum TemplateValue {
+    Text(String),
+    Number(u32),
+}
+
+/// Lee el valor de un campo de plantilla del tag
+///
+/// `year` usa el año de TYER o, si no existe, el de la fecha de grabación (TDRC).
+fn template_value(tag: &Tag, field: &str) -> Result<Option<TemplateValue>, String> {
+    let text = |value: Option<&str>| value.map(|v| TemplateValue::Text(v.to_string()));
+    let value = match field {
+        "title" => text(tag.title()),
+        "artist" => text(tag.artist()),
+        "album" => text(tag.album()),
+        "album_artist" => text(tag.album_artist()),
+        "genre" => text(tag.genre()),
+        "composer" => text(tag.get("TCOM").and_then(|f| f.content().text())),
+        "date" => tag
+            .date_recorded()
+            .map(|d| TemplateValue::Text(d.to_string())),
+        "track" => tag.track().map(TemplateValue::Number),
+        "disc" | "season" => tag.disc().map(TemplateValue::Number),
+        "year" => tag
+            .year()
+            .or_else(|| tag.date_recorded().map(|d| d.year))
+            .map(|y| TemplateValue::Number(y.max(0) as u32)),
+        _ => {
+            return Err(format!(
+                "Campo desconocido en la plantilla: '{}'. Campos válidos: {}",
+                field,
+                TEMPLATE_FIELDS.join(", ")
+            ));
+        }
+    };
+    Ok(value)
+}
+
+/// Limpia un texto para que sea válido como nombre de archivo
+///
+/// Sustituye separadores de ruta, caracteres reservados en Windows
+/// (`\ / : * ? " < > |`) y caracteres de control por `_`, y elimina los
+/// espacios y puntos del principio y del final. Un resultado vacío se
+/// convierte en `_`.
+pub fn sanitize_filename(name: &str) -> String {
+    let replaced: String = name
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+
+    let trimmed = replaced.trim_matches(|c: char| c.is_whitespace() || c == '.');
+    if trimmed.is_empty() {
+        "_".to_string()
+    } else {
+        trimmed.to_string()
+    }
+}
+
+/// Genera una ruta a partir de una plantilla y los valores del tag
+///
+/// Los campos se escriben entre llaves (`{title}`) y los numéricos aceptan
+/// relleno con ceros (`{track:02}` → `03`). `{{` y `}}` producen llaves
+/// literales. Los valores se limpian con `sanitize_filename`, así que solo
+/// las `/` de la propia plantilla crean directorios.
+///
+/// # Ejemplo
+///
+/// `"{album_artist}/{album}/{track:02} - {title}.mp3"` →
+/// `"Queen/A Night at the Opera/11 - Bohemian Rhapsody.mp3"`
+///
+/// # Errores
+///
+/// Retorna un error si la plantilla está mal formada, usa un campo
+/// desconocido o un campo que no existe en el tag.
+pub fn render_template(template: &str, tag: &Tag) -> Result<String, String> {
+    let mut output = String::new();
+    let mut chars = template.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                output.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                output.push('}');
+            }
+            '{' => {
+                let mut spec = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => spec.push(c),
+                        None => return Err(format!("Falta '}}' en la plantilla: '{}'", template)),
+                    }
+                }
+
+                let (field, format) = match spec.split_once(':') {
+                    Some((field, format)) => (field.trim(), Some(format.trim())),
+                    None => (spec.trim(), None),
+                };
+
+                let value = template_value(tag, field)?
+                    .ok_or_else(|| format!("El tag no tiene el campo '{}'", field))?;
+
+                let rendered = match (value, format) {
+                    (TemplateValue::Text(text), None) => text,
+                    (TemplateValue::Number(n), None) => n.to_string(),
+                    (TemplateValue::Number(n), Some(format)) => {
+                        let width: usize = format
+                            .trim_start_matches('0')
+                            .parse()
+                            .map_err(|_| format!("Formato no válido en '{{{}}}'", spec))?;
+                        format!("{:0width$}", n, width = width)
+                    }
+                    (TemplateValue::Text(_), Some(_)) => {
+                        return Err(format!(
+                            "El campo '{}' no es numérico y no admite formato",
+                            field
+                        ));
+                    }
+                };
+                output.push_str(&sanitize_filename(&rendered));
+            }
+            '}' => return Err(format!("'}}' sin abrir en la plantilla: '{}'", template)),
+            c => output.push(c),
+        }
+    }
+
+    Ok(output)
+}
+
+/// Elemento de un patrón de `parse_filename`
+enum PatternToken {
+    Literal(String),
+    Field(String),
+}
+
+/// Divide un patrón `%campo%` en literales y campos
+fn tokenize_pattern(pattern: &str) -> Result<Vec<PatternToken>, String> {
+    let mut tokens = Vec::new();
+    let mut rest = pattern;
+
+    while let Some(start) = rest.find('%') {
+        let after = &rest[start + 1..];
+        let end = after
+            .find('%')
+            .ok_or_else(|| format!("Falta '%' de cierre en el patrón: '{}'", pattern))?;
+        let literal = &rest[..start];
+        let name = &after[..end];
+
+        if name.is_empty() {
+            // "%%" es un '%' literal
+            tokens.push(PatternToken::Literal(format!("{}%", literal)));
+        } else {
+            if !literal.is_empty() {
+                tokens.push(PatternToken::Literal(literal.to_string()));
+            }
+            if name != "ignore" && !TEMPLATE_FIELDS.contains(&name) {
+                return Err(format!(
+                    "Campo desconocido en el patrón: '{}'. Campos válidos: {}, ignore",
+                    name,
+                    TEMPLATE_FIELDS.join(", ")
+                ));
+            }
+            tokens.push(PatternToken::Field(name.to_string()));
+        }
+        rest = &after[end + 1..];
+    }
+
+    if !rest.is_empty() {
+        tokens.push(PatternToken::Literal(rest.to_string()));
+    }
+    Ok(tokens)
+}
+
+/// Empareja los tokens con el texto; cada campo captura lo mínimo posible
+/// (sin cruzar `/`) y se retrocede si el resto no encaja.
+fn match_pattern(
+    tokens: &[PatternToken],
+    text: &str,
+    captures: &mut Vec<(String, String)>,
+) -> bool {
+    match tokens.split_first() {
+        None => text.is_empty(),
+        Some((PatternToken::Literal(literal), rest)) => text
+            .strip_prefix(literal.as_str())
+            .is_some_and(|remaining| match_pattern(rest, remaining, captures)),
+        Some((PatternToken::Field(name), rest)) => {
+            let ends = text
+                .char_indices()
+                .map(|(i, _)| i)
+                .skip(1)
+                .chain(std::iter::once(text.len()));
+            for end in ends {
+                let value = &text[..end];
+                if value.contains('/') {
+                    break;
+                }
+                captures.push((name.clone(), value.trim().to_string()));
+                if match_pattern(rest, &text[end..], captures) {
+                    return true;
+                }
+                captures.pop();
+            }
+            false
+        }
+    }
+}
+
+/// Extrae campos de la ruta de un archivo según un patrón
+///
+/// Los campos se escriben entre `%` (`%artist% - %album%/%track% - %title%`),
+/// `%ignore%` descarta una parte y `%%` es un `%` literal. Cada `/` del
+/// patrón corresponde a un nivel de directorio: el patrón se compara con los
+/// últimos componentes de la ruta, sin la extensión del archivo.
+///
+/// # Errores
+///
+/// Retorna un error si el patrón está mal formado, usa un campo desconocido
+/// o la ruta no encaja con el patrón.
+///
+/// # Retorna
+///
+/// Los pares `(campo, valor)` capturados, en el orden del patrón
+pub fn parse_filename(path: &Path, pattern: &str) -> Result<Vec<(String, String)>, String> {
+    let tokens = tokenize_pattern(pattern)?;
+
+    let levels = pattern.matches('/').count() + 1;
+    let mut components: Vec<String> = path
+        .with_extension("")
+        .components()
+        .filter_map(|c| match c {
+            std::path::Component::Normal(part) => Some(part.to_string_lossy().to_string()),
+            _ => None,
+        })
+        .collect();
+    if components.len() < levels {
+        return Err(format!(
+            "La ruta '{}' tiene menos niveles que el patrón",
+            path.display()
+        ));
+    }
+    let text = components.split_off(components.len() - levels).join("/");
+
+    let mut captures = Vec::new();
+    if !match_pattern(&tokens, &text, &mut captures) {
+        return Err(format!("'{}' no encaja con el patrón '{}'", text, pattern));
+    }
+    captures.retain(|(name, _)| name != "ignore");
+    Ok(captures)
+}
+
+/// Aplica al tag los campos obtenidos con `parse_filename`
+///
+/// Los valores se convierten al tipo de cada campo y se aplican con
+/// `apply_metadata`.
+///
+/// # Errores
+///
+/// Retorna un error si un campo numérico (`track`, `disc`/`season`, `year`)
+/// o la fecha no tienen un formato válido.
+///
+/// # Retorna
+///
+/// `true` si se aplicó al menos un cambio
+pub fn apply_filename_fields(tag: &mut Tag, fields: &[(String, String)]) -> Result<bool, String> {
+    let get = |name: &str| {
+        fields
+            .iter()
+            .rev()
+            .find(|(field, _)| field == name)
+            .map(|(_, value)| value.as_str())
+    };
+    let number = |name: &str| -> Result<Option<u32>, String> {
+        get(name)
+            .map(|value| {
+                value
+                    .parse::<u32>()
+                    .map_err(|_| format!("Valor numérico no válido para '{}': '{}'", name, value))
+            })
+            .transpose()
+    };
+
+    let year = get("year")
+        .map(|value| {
+            value
+                .parse::<i32>()
+                .map_err(|_| format!("Año no válido: '{}'", value))
+        })
+        .transpose()?;
+    let date = get("date");
+    if let Some(date) = date {
+        date.parse::<id3::Timestamp>()
+            .map_err(|_| format!("Fecha no válida: '{}'", date))?;
+    }
+    let artists: Vec<String> = get("artist").map(str::to_string).into_iter().collect();
+
+    Ok(apply_metadata(
+        tag,
+        get("title"),
+        &artists,
+        get("album"),
+        year,
+        get("genre"),
+        number("track")?,
+        number("disc")?.or(number("season")?),
+        date,
+        None,
+        get("composer"),
+        None,
+        None,
+        get("album_artist"),
+    ))
+}
+
+/// Detecta colisiones en una lista de renombrados `(origen, destino)`
+///
+/// Hay colisión si dos archivos van al mismo destino o si el destino ya
+/// existe y no es el propio archivo de origen.
+///
+/// # Retorna
+///
+/// Los índices de los renombrados en conflicto junto con el motivo
+pub fn find_rename_collisions(moves: &[(PathBuf, PathBuf)]) -> Vec<(usize, String)> {
+    let mut collisions = Vec::new();
+
+    for (i, (from, to)) in moves.iter().enumerate() {
+        if let Some(first) = moves[..i].iter().position(|(_, other)| other == to) {
+            collisions.push((
+                i,
+                format!(
+                    "'{}' tendría el mismo destino que '{}': '{}'",
+                    from.display(),
+                    moves[first].0.display(),
+                    to.display()
+                ),
+            ));
+        } else if to.exists() && from != to {
+            collisions.push((i, format!("El destino '{}' ya existe", to.display())));
+        }
+    }
+
+    collisions
+}
+
+/// Mueve un archivo creando los directorios necesarios
+///
+/// Si `fs::rename` falla (por ejemplo, entre sistemas de archivos distintos)
+/// se copia el archivo y se elimina el original.
+pub fn move_file(from: &Path, to: &Path) -> std::io::Result<()> {
+    if let Some(parent) = to.parent()
+        && !parent.as_os_str().is_empty()
+    {
+        fs::create_dir_all(parent)?;
+    }
+
+    if fs::rename(from, to).is_err() {
+        fs::copy(from, to)?;
+        fs::remove_file(from)?;
+    }
+    Ok(())
+}
+
+/// Frames de ID3v2.4 sin equivalente en ID3v2.3
+const V24_ONLY_FRAMES: &[&str] = &[
+    "TDEN", "TDRL", "TDTG", "TMOO", "TPRO", "TSST", "ASPI", "EQU2", "RVA2", "SEED", "SIGN",
+];
+
+/// Frames de ID3v2.3 obsoletos en ID3v2.4 y sin conversión directa
+const V23_ONLY_FRAMES: &[&str] = &["TRDA", "TSIZ", "EQUA", "RVAD"];
+
+/// Resultado de convertir un tag a otra versión de ID3
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ConversionReport {
+    /// Frames transformados sin pérdida de información
+    pub changes: Vec<String>,
+    /// Frames transformados o eliminados con pérdida de información
+    pub lossy: Vec<String>,
+}
+
+/// Convierte los frames del tag a los equivalentes de la versión indicada
+///
+/// # Hacia ID3v2.3
+///
+/// * TDRC → TYER + TDAT (DDMM) + TIME (HHMM); se pierden los segundos
+/// * TDOR → TORY (solo el año)
+/// * TIPL y TMCL → IPLS
+/// * Se eliminan los frames exclusivos de v2.4 (TMOO, TSST, RVA2...)
+///
+/// # Hacia ID3v2.4
+///
+/// * TYER + TDAT + TIME → TDRC (si no existe ya); TYER se conserva porque
+///   es el año que escribe `edit --year` y que leen la mayoría de
+///   reproductores, y TDAT/TIME se eliminan
+/// * TORY → TDOR
+/// * IPLS → TIPL
+/// * Se eliminan los frames obsoletos (TRDA, TSIZ, EQUA, RVAD)
+///
+/// ID3v2.2 no se convierte: el tag se deja sin cambios.
+pub fn convert_tag(tag: &mut Tag, version: Version) -> ConversionReport {
+    let mut report = ConversionReport::default();
+
+    match version {
+        Version::Id3v23 => convert_to_v23(tag, &mut report),
+        Version::Id3v24 => convert_to_v24(tag, &mut report),
+        Version::Id3v22 => {}
+    }
+
+    report
+}
+
+fn convert_to_v23(tag: &mut Tag, report: &mut ConversionReport) {
+    if tag.get("TDRC").is_some() {
+        match tag.date_recorded() {
+            Some(ts) => {
+                tag.remove("TDRC");
+                tag.set_text("TYER", format!("{:04}", ts.year));
+                if let (Some(month), Some(day)) = (ts.month, ts.day) {
+                    tag.set_text("TDAT", format!("{:02}{:02}", day, month));
+                } else if ts.month.is_some() {
+                    report.lossy.push(format!(
+                        "TDRC: se pierde el mes de '{}' (TDAT requiere día)",
+                        ts
+                    ));
+                }
+                if let (Some(hour), Some(minute)) = (ts.hour, ts.minute) {
+                    tag.set_text("TIME", format!("{:02}{:02}", hour, minute));
+                }
+                if ts.second.is_some() {
+                    report
+                        .lossy
+                        .push(format!("TDRC: se pierden los segundos de '{}'", ts));
+                }
+                report
+                    .changes
+                    .push(format!("TDRC → TYER/TDAT/TIME ({})", ts));
+            }
+            None => {
+                tag.remove("TDRC");
+                report
+                    .lossy
+                    .push("TDRC: fecha no válida, se elimina".to_string());
+            }
+        }
+    }
+
+    if let Some(ts) = tag.original_date_released() {
+        tag.remove("TDOR");
+        tag.set_text("TORY", format!("{:04}", ts.year));
+        if ts.month.is_some() {
+            report
+                .lossy
+                .push(format!("TDOR → TORY: solo se conserva el año de '{}'", ts));
+        } else {
+            report.changes.push(format!("TDOR → TORY ({})", ts.year));
+        }
+    }
+
+    let mut items = Vec::new();
+    for id in ["TIPL", "TMCL"] {
+        for frame in tag.remove(id) {
+            if let Some(list) = frame.content().involved_people_list() {
+                items.extend(list.items.iter().cloned());
+            }
+            if id == "TMCL" {
+                report
+                    .lossy
+                    .push("TMCL: los músicos se fusionan en IPLS".to_string());
+            } else {
+                report.changes.push("TIPL → IPLS".to_string());
+            }
+        }
+    }
+    if !items.is_empty() {
+        tag.add_frame(Frame::with_content(
+            "IPLS",
+            Content::InvolvedPeopleList(InvolvedPeopleList { items }),
+        ));
+    }
+
+    for id in V24_ONLY_FRAMES {
+        for frame in tag.remove(id) {
+            report.lossy.push(format!(
+                "{} ({}) no existe en ID3v2.3, se elimina",
+                id,
+                frame.name()
+            ));
+        }
+    }
+}
+
+fn convert_to_v24(tag: &mut Tag, report: &mut ConversionReport) {
+    let year = tag.year();
+    let date = frame_text(tag, "TDAT");
+    let time = frame_text(tag, "TIME");
+    let had_date_time = date.is_some() || time.is_some();
+
+    if had_date_time {
+        tag.remove("TDAT");
+        tag.remove("TIME");
+    }
+
+    if let Some(year) = year {
+        if tag.get("TDRC").is_some() {
+            if had_date_time {
+                report
+                    .lossy
+                    .push("TDAT/TIME: ya existe TDRC, se descartan".to_string());
+            }
+        } else {
+            // TDAT es DDMM y TIME es HHMM
+            let pair = |text: &Option<String>| -> Option<(u8, u8)> {
+                let text = text.as_deref()?;
+                if text.len() != 4 {
+                    return None;
+                }
+                Some((text.get(..2)?.parse().ok()?, text.get(2..)?.parse().ok()?))
+            };
+            let (day, month) = pair(&date).unzip();
+            let (hour, minute) = pair(&time).unzip();
+            let timestamp = Timestamp {
+                year,
+                month,
+                day,
+                hour: month.and(hour),
+                minute: month.and(minute),
+                second: None,
+            };
+            tag.set_date_recorded(timestamp);
+            report
+                .changes
+                .push(format!("TYER/TDAT/TIME → TDRC ({})", timestamp));
+        }
+    } else if had_date_time {
+        report
+            .lossy
+            .push("TDAT/TIME sin TYER, se eliminan".to_string());
+    }
+
+    if let Some(original_year) = frame_text(tag, "TORY") {
+        tag.remove("TORY");
+        match original_year.trim().parse::<i32>() {
+            Ok(year) if tag.get("TDOR").is_none() => {
+                tag.set_original_date_released(Timestamp {
+                    year,
+                    month: None,
+                    day: None,
+                    hour: None,
+                    minute: None,
+                    second: None,
+                });
+                report.changes.push(format!("TORY → TDOR ({})", year));
+            }
+            _ => report.lossy.push(format!(
+                "TORY '{}' no se puede convertir, se elimina",
+                original_year
+            )),
+        }
+    }
+
+    for frame in tag.remove("IPLS") {
+        if let Some(list) = frame.content().involved_people_list() {
+            tag.add_frame(Frame::with_content(
+                "TIPL",
+                Content::InvolvedPeopleList(list.clone()),
+            ));
+            report.changes.push("IPLS → TIPL".to_string());
+        }
+    }
+
+    for id in V23_ONLY_FRAMES {
+        for frame in tag.remove(id) {
+            report.lossy.push(format!(
+                "{} ({}) está obsoleto en ID3v2.4, se elimina",
+                id,
+                frame.name()
+            ));
+        }
+    }
+}
+
+/// Tamaño a partir del cual `lint` considera excesiva una imagen incrustada
+pub const LINT_MAX_PICTURE_BYTES: usize = 1024 * 1024;
+
+/// Frames de ID3v2.3 que no deberían aparecer en un tag ID3v2.4
+///
+/// TYER no se incluye: `edit --year` lo escribe también en ID3v2.4 y es el
+/// frame de año que leen la mayoría de reproductores, así que
+/// [`convert_tag`] lo conserva al pasar a ID3v2.4.
+const OBSOLETE_IN_V24_FRAMES: &[&str] = &["TDAT", "TIME", "TORY", "IPLS"];
+
+/// Frames de fecha con formato de timestamp ID3v2.4
+const TIMESTAMP_FRAMES: &[&str] = &["TDRC", "TDOR", "TDRL", "TDEN", "TDTG"];
+
+/// Problema detectado por [`lint_tag`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LintIssue {
+    /// Código estable del problema (`missing-field`, `duplicate-frame`...)
+    pub code: &'static str,
+    /// Frame afectado, si lo hay
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub frame: Option<String>,
+    /// Descripción legible del problema
+    pub message: String,
+}
+
+impl LintIssue {
+    fn new(code: &'static str, frame: Option<&str>, message: String) -> Self {
+        Self {
+            code,
+            frame: frame.map(str::to_string),
+            message,
+        }
+    }
+}
+
+/// Detecta texto mal decodificado (UTF-8 leído como Latin-1, `Ã©`, `â€™`...)
+/// o con caracteres de sustitución
+fn looks_like_mojibake(text: &str) -> bool {
+    if text.contains('\u{FFFD}') || text.contains("â€") {
+        return true;
+    }
+    let chars: Vec<char> = text.chars().collect();
+    chars
+        .windows(2)
+        .any(|pair| matches!(pair[0], 'Ã' | 'Â') && ('\u{80}'..='\u{BF}').contains(&pair[1]))
+}
+
+/// Comprueba que un timestamp ID3v2.4 se pueda interpretar y que sus
+/// componentes estén en rango (mes 1-12, día 1-31, hora 0-23...)
+///
+/// El parser de id3 ignora lo que sigue a un prefijo válido ("17/05/2024"
+/// se lee como el año 17), así que el texto debe coincidir con su forma
+/// canónica.
+fn is_valid_timestamp(text: &str) -> bool {
+    let text = text.trim();
+    let Ok(timestamp) = text.parse::<Timestamp>() else {
+        return false;
+    };
+    timestamp.to_string() == text
+        && timestamp
+            .month
+            .is_none_or(|month| (1..=12).contains(&month))
+        && timestamp.day.is_none_or(|day| (1..=31).contains(&day))
+        && timestamp.hour.is_none_or(|hour| hour < 24)
+        && timestamp.minute.is_none_or(|minute| minute < 60)
+        && timestamp.second.is_none_or(|second| second < 60)
+}
+
+/// Revisa la calidad de un tag
+///
+/// Comprueba:
+/// * Que existan título, artista y álbum
+/// * Frames duplicados (misma clave; en USLT, mismo idioma y descripción)
+/// * Fechas no válidas
+/// * Pista, disco o temporada mayor que su total
+/// * Espacios al principio o al final de los textos
+/// * Texto mal codificado (mojibake)
+/// * Imágenes de más de [`LINT_MAX_PICTURE_BYTES`]
+/// * Frames obsoletos de ID3v2.3 en un tag ID3v2.4
+///
+/// # Retorna
+///
+/// La lista de problemas; vacía si el tag es correcto
+pub fn lint_tag(tag: &Tag) -> Vec<LintIssue> {
+    let mut issues = Vec::new();
+
+    for (id, name) in [("TIT2", "título"), ("TPE1", "artista"), ("TALB", "álbum")] {
+        if frame_text(tag, id).is_none_or(|text| text.trim().is_empty()) {
+            issues.push(LintIssue::new(
+                "missing-field",
+                Some(id),
+                format!("Falta el {}", name),
+            ));
+        }
+    }
+
+    let mut seen: Vec<String> = Vec::new();
+    for frame in tag.frames() {
+        let key = frame_key(frame);
+        if seen.contains(&key) {
+            issues.push(LintIssue::new(
+                "duplicate-frame",
+                Some(frame.id()),
+                format!("Frame {} duplicado", frame.id()),
+            ));
+        } else {
+            seen.push(key);
+        }
+    }
+    for id in TIMESTAMP_FRAMES {
+        if let Some(text) = frame_text(tag, id)
+            && !is_valid_timestamp(&text)
+        {
+            issues.push(LintIssue::new(
+                "invalid-date",
+                Some(id),
+                format!("Fecha no válida en {}: '{}'", id, text),
+            ));
+        }
+    }
+    if let Some(text) = frame_text(tag, "TYER")
+        && (text.len() != 4 || text.parse::<u16>().is_err())
+    {
+        issues.push(LintIssue::new(
+            "invalid-date",
+            Some("TYER"),
+            format!("Año no válido en TYER: '{}'", text),
+        ));
+    }
+
+    for (id, number, total, name) in [
+        ("TRCK", tag.track(), tag.total_tracks(), "La pista"),
+        (
+            "TPOS",
+            tag.disc(),
+            tag.total_discs(),
+            "El disco o temporada",
+        ),
+    ] {
+        match (number, total) {
+            (Some(number), Some(total)) if number > total => issues.push(LintIssue::new(
+                "number-exceeds-total",
+                Some(id),
+                format!("{} {} supera el total {}", name, number, total),
+            )),
+            (None, _) if tag.get(id).is_some() => issues.push(LintIssue::new(
+                "invalid-number",
+                Some(id),
+                format!(
+                    "{} no es un número válido: '{}'",
+                    id,
+                    frame_text(tag, id).unwrap_or_default()
+                ),
+            )),
+            _ => {}
+        }
+    }
+
+    for frame in tag.frames() {
+        let texts: Vec<&str> = match frame.content() {
+            Content::Text(text) => text.split('\0').collect(),
+            Content::ExtendedText(ext) => vec![ext.value.as_str()],
+            Content::Comment(comment) => vec![comment.text.as_str()],
+            Content::Lyrics(lyrics) => vec![lyrics.text.as_str()],
+            _ => continue,
+        };
+        let is_text = matches!(frame.content(), Content::Text(_) | Content::ExtendedText(_));
+
+        if is_text && texts.iter().any(|text| text.trim() != *text) {
+            issues.push(LintIssue::new(
+                "whitespace",
+                Some(frame.id()),
+                format!("{} tiene espacios al principio o al final", frame.id()),
+            ));
+        }
+        if texts.iter().any(|text| looks_like_mojibake(text)) {
+            issues.push(LintIssue::new(
+                "mojibake",
+                Some(frame.id()),
+                format!("{} parece texto mal codificado", frame.id()),
+            ));
+        }
+    }
+
+    for picture in tag.pictures() {
+        if picture.data.len() > LINT_MAX_PICTURE_BYTES {
+            issues.push(LintIssue::new(
+                "oversized-picture",
+                Some("APIC"),
+                format!(
+                    "Imagen {} de {} (máximo recomendado {}); usa --cover-max-size",
+                    picture_type_name(picture.picture_type),
+                    format_bytes(picture.data.len()),
+                    format_bytes(LINT_MAX_PICTURE_BYTES)
+                ),
+            ));
+        }
+    }
+
+    if tag.version() == Version::Id3v24 {
+        for frame in tag.frames() {
+            let id = frame.id();
+            if V23_ONLY_FRAMES.contains(&id) || OBSOLETE_IN_V24_FRAMES.contains(&id) {
+                issues.push(LintIssue::new(
+                    "obsolete-frame",
+                    Some(id),
+                    format!(
+                        "{} es un frame de ID3v2.3 obsoleto en ID3v2.4 (usa convert --to 2.4)",
+                        id
+                    ),
+                ));
+            }
+        }
+    }
+
+    issues
+}
+
+/// Capítulo a añadir al tag (frame CHAP)
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct NewChapter {
+    /// Inicio en milisegundos
+    pub start: u32,
+    /// Fin en milisegundos; si falta se usa el inicio del siguiente capítulo
+    pub end: Option<u32>,
+    /// Título del capítulo (TIT2)
+    pub title: String,
+    /// URL asociada (WXXX)
+    pub url: Option<String>,
+    /// Imagen del capítulo (APIC)
+    pub image: Option<PathBuf>,
+}
+
+/// Convierte una marca de tiempo de capítulo a milisegundos
+///
+/// Acepta `HH:MM:SS`, `MM:SS` o `SS`, con fracción de segundo opcional
+/// (`01:02:03.500`).
+///
+/// # Errores
+///
+/// Retorna un error si el formato no es válido o los minutos o segundos
+/// superan 59.
+pub fn parse_chapter_time(text: &str) -> Result<u32, String> {
+    let invalid = || format!("Marca de tiempo no válida: '{}' (usa HH:MM:SS)", text);
+
+    let (clock, fraction) = match text.trim().split_once('.') {
+        Some((clock, fraction)) => (clock, fraction),
+        None => (text.trim(), ""),
+    };
+    if fraction.len() > 3 || !fraction.chars().all(|c| c.is_ascii_digit()) {
+        return Err(invalid());
+    }
+    let millis = match fraction.len() {
+        0 => 0,
+        len => fraction.parse::<u32>().map_err(|_| invalid())? * 10u32.pow(3 - len as u32),
+    };
+
+    let parts = clock
+        .split(':')
+        .map(|part| part.parse::<u32>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| invalid())?;
+    let seconds = match parts.as_slice() {
+        [s] => *s,
+        [m, s] if *s < 60 => m * 60 + s,
+        [h, m, s] if *m < 60 && *s < 60 => h * 3600 + m * 60 + s,
+        _ => return Err(invalid()),
+    };
+
+    seconds
+        .checked_mul(1000)
+        .and_then(|ms| ms.checked_add(millis))
+        .ok_or_else(invalid)
+}
+
+/// Formatea milisegundos como `HH:MM:SS.mmm`
+pub fn format_chapter_time(millis: u32) -> String {
+    format!(
+        "{:02}:{:02}:{:02}.{:03}",
+        millis / 3_600_000,
+        millis / 60_000 % 60,
+        millis / 1000 % 60,
+        millis % 1000
+    )
+}
+
+/// Devuelve los capítulos del tag ordenados por inicio
+pub fn sorted_chapters(tag: &Tag) -> Vec<&Chapter> {
+    let mut chapters: Vec<&Chapter> = tag.chapters().collect();
+    chapters.sort_by_key(|chapter| chapter.start_time);
+    chapters
+}
+
+/// Añade un capítulo (CHAP) y reconstruye la tabla de contenidos
+///
+/// # Errores
+///
+/// Retorna un error si el fin es anterior al inicio o no se puede leer la
+/// imagen del capítulo.
+pub fn add_chapter(tag: &mut Tag, chapter: &NewChapter) -> Result<(), Box<dyn std::error::Error>> {
+    if chapter.end.is_some_and(|end| end <= chapter.start) {
+        return Err(format!(
+            "El fin del capítulo '{}' debe ser posterior a su inicio",
+            chapter.title
+        )
+        .into());
+    }
+
+    let mut frames = vec![Frame::text("TIT2", chapter.title.clone())];
+    if let Some(url) = &chapter.url {
+        frames.push(Frame::with_content(
+            "WXXX",
+            Content::ExtendedLink(ExtendedLink {
+                description: String::new(),
+                link: url.clone(),
+            }),
+        ));
+    }
+    if let Some(image) = &chapter.image {
+        let data =
+            fs::read(image).map_err(|e| format!("No se pudo leer '{}': {}", image.display(), e))?;
+        let mime_type = image_mime_type(image, &data)?;
+        frames.push(Frame::with_content(
+            "APIC",
+            Content::Picture(Picture {
+                mime_type: mime_type.to_string(),
+                picture_type: PictureType::Other,
+                description: chapter.title.clone(),
+                data,
+            }),
+        ));
+    }
+
+    tag.add_frame(Chapter {
+        // Identificador provisional: rebuild_chapters renumera todos
+        element_id: format!("new{}", tag.chapters().count()),
+        start_time: chapter.start,
+        end_time: chapter.end.unwrap_or(chapter.start),
+        start_offset: u32::MAX,
+        end_offset: u32::MAX,
+        frames,
+    });
+    rebuild_chapters(tag);
+    Ok(())
+}
+
+/// Elimina capítulos por su posición (empezando en 1, en orden de inicio)
+/// o por su identificador, y reconstruye la tabla de contenidos
+///
+/// Todas las posiciones se refieren a la lista antes de eliminar nada.
+///
+/// # Errores
+///
+/// Retorna un error, sin modificar el tag, si algún capítulo no existe.
+pub fn remove_chapters(tag: &mut Tag, selectors: &[String]) -> Result<Vec<Chapter>, String> {
+    let chapters = sorted_chapters(tag);
+    let ids = selectors
+        .iter()
+        .map(|selector| {
+            let found = match selector.parse::<usize>() {
+                Ok(index) => chapters.get(index.wrapping_sub(1)),
+                Err(_) => chapters.iter().find(|c| &c.element_id == selector),
+            };
+            found
+                .map(|chapter| chapter.element_id.clone())
+                .ok_or_else(|| format!("No existe el capítulo '{}'", selector))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut removed = Vec::new();
+    for frame in tag.remove("CHAP") {
+        match frame.content().chapter() {
+            Some(chapter) if ids.contains(&chapter.element_id) => removed.push(chapter.clone()),
+            _ => {
+                tag.add_frame(frame);
+            }
+        }
+    }
+
+    rebuild_chapters(tag);
+    Ok(removed)
+}
+
+/// Elimina todos los capítulos y la tabla de contenidos
+pub fn remove_all_chapters(tag: &mut Tag) -> usize {
+    tag.remove("CTOC");
+    tag.remove("CHAP").len()
+}
+
+/// Ordena los capítulos, los renumera (`chp0`, `chp1`...), ajusta los
+/// finales y actualiza las tablas de contenidos (CTOC)
+///
+/// Un capítulo sin fin, o cuyo fin solapa con el siguiente, termina donde
+/// empieza el siguiente. El último termina en la duración del audio (TLEN)
+/// si se conoce.
+///
+/// Las tablas existentes se conservan (con sus subframes y sus tablas
+/// anidadas); solo se actualizan sus listas de elementos: los capítulos se
+/// renombran, los eliminados desaparecen y los que no figuran en ninguna
+/// tabla se añaden a la de nivel superior, que se crea (`toc`) si no existe.
+/// Sin capítulos se eliminan todas las tablas.
+///
+/// # Retorna
+///
+/// `true` si el último capítulo ha quedado sin fin porque el tag no tiene
+/// TLEN
+pub fn rebuild_chapters(tag: &mut Tag) -> bool {
+    let mut chapters: Vec<Chapter> = tag
+        .remove("CHAP")
+        .iter()
+        .filter_map(|frame| frame.content().chapter().cloned())
+        .collect();
+    let mut tocs: Vec<TableOfContents> = tag
+        .remove("CTOC")
+        .iter()
+        .filter_map(|frame| frame.content().table_of_contents().cloned())
+        .collect();
+
+    if chapters.is_empty() {
+        return false;
+    }
+
+    chapters.sort_by_key(|chapter| chapter.start_time);
+    let old_ids: Vec<String> = chapters.iter().map(|c| c.element_id.clone()).collect();
+    let starts: Vec<u32> = chapters.iter().skip(1).map(|c| c.start_time).collect();
+    let duration = tag.duration();
+    let mut open_end = false;
+
+    for (index, chapter) in chapters.iter_mut().enumerate() {
+        chapter.element_id = format!("chp{}", index);
+        match starts.get(index) {
+            Some(&next) if chapter.end_time <= chapter.start_time || chapter.end_time > next => {
+                chapter.end_time = next;
+            }
+            None if chapter.end_time <= chapter.start_time => match duration {
+                Some(duration) if duration > chapter.start_time => chapter.end_time = duration,
+                _ => open_end = true,
+            },
+            _ => {}
+        }
+    }
+
+    let top_level = match tocs.iter().position(|toc| toc.top_level) {
+        Some(position) => position,
+        None => {
+            tocs.push(TableOfContents {
+                element_id: "toc".to_string(),
+                top_level: true,
+                ordered: true,
+                elements: Vec::new(),
+                frames: Vec::new(),
+            });
+            tocs.len() - 1
+        }
+    };
+
+    // Cada elemento es una tabla anidada (None) o un capítulo (su posición)
+    let toc_ids: Vec<String> = tocs.iter().map(|toc| toc.element_id.clone()).collect();
+    let mut listed = vec![false; chapters.len()];
+    let mut elements: Vec<Vec<(Option<usize>, String)>> = Vec::new();
+    for toc in &tocs {
+        let mut toc_elements = Vec::new();
+        for element in &toc.elements {
+            if toc_ids.contains(element) {
+                toc_elements.push((None, element.clone()));
+            } else if let Some(index) = old_ids.iter().position(|id| id == element) {
+                listed[index] = true;
+                toc_elements.push((Some(index), chapters[index].element_id.clone()));
+            }
+        }
+        elements.push(toc_elements);
+    }
+    for (index, chapter) in chapters.iter().enumerate() {
+        if !listed[index] {
+            elements[top_level].push((Some(index), chapter.element_id.clone()));
+        }
+    }
+
+    for (toc, mut toc_elements) in tocs.iter_mut().zip(elements) {
+        if toc_elements.iter().all(|(index, _)| index.is_some()) {
+            toc_elements.sort_by_key(|(index, _)| *index);
+        }
+        toc.elements = toc_elements.into_iter().map(|(_, id)| id).collect();
+    }
+
+    for toc in tocs {
+        tag.add_frame(toc);
+    }
+    for chapter in chapters {
+        tag.add_frame(chapter);
+    }
+
+    open_end
+}
+
+/// Interpreta una lista de capítulos en texto, una línea por capítulo:
+/// `HH:MM:SS Título` (se ignoran las líneas vacías y las que empiezan por `#`)
+///
+/// # Errores
+///
+/// Retorna un error indicando la línea que no se puede interpretar.
+pub fn parse_chapter_list(text: &str) -> Result<Vec<NewChapter>, String> {
+    let mut chapters = Vec::new();
+
+    for (number, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (time, title) = line
+            .split_once(char::is_whitespace)
+            .ok_or_else(|| format!("Línea {}: falta el título del capítulo", number + 1))?;
+        let start = parse_chapter_time(time).map_err(|e| format!("Línea {}: {}", number + 1, e))?;
+        chapters.push(NewChapter {
+            start,
+            title: title.trim().to_string(),
+            ..NewChapter::default()
+        });
+    }
+
+    if chapters.is_empty() {
+        return Err("La lista no contiene capítulos".to_string());
+    }
+    Ok(chapters)
+}
+
+/// Documento JSON de capítulos
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ChapterDocument {
+    /// Podlove Simple Chapters: `[{"start": "00:00:00.000", "title": ...}]`
+    Podlove(Vec<PodloveChapter>),
+    /// Podcasting 2.0: `{"chapters": [{"startTime": 0, "title": ...}]}`
+    Podcasting20 { chapters: Vec<JsonChapter> },
+}
+
+#[derive(Deserialize)]
+struct PodloveChapter {
+    start: String,
+    title: String,
+    #[serde(default)]
+    href: Option<String>,
+    #[serde(default)]
+    image: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JsonChapter {
+    start_time: f64,
+    #[serde(default)]
+    end_time: Option<f64>,
+    #[serde(default)]
+    title: Option<String>,
+    #[serde(default)]
+    url: Option<String>,
+    #[serde(default)]
+    img: Option<String>,
+}
+
+/// Interpreta capítulos en JSON (Podlove Simple Chapters o Podcasting 2.0)
+///
+/// Las imágenes con ruta local se resuelven desde `base_dir`; las URLs
+/// remotas se ignoran.
+///
+/// # Errores
+///
+/// Retorna un error si el JSON no sigue ninguno de los dos formatos o una
+/// marca de tiempo no es válida.
+pub fn parse_chapter_json(text: &str, base_dir: &Path) -> Result<Vec<NewChapter>, String> {
+    let document: ChapterDocument = serde_json::from_str(text)
+        .map_err(|_| "JSON de capítulos no válido (se espera Podlove o Podcasting 2.0)")?;
+
+    let non_empty = |value: Option<String>| value.filter(|v| !v.trim().is_empty());
+    let local_image = |image: Option<String>| {
+        non_empty(image)
+            .filter(|image| !image.starts_with("http://") && !image.starts_with("https://"))
+            .map(|image| base_dir.join(image))
+    };
+    let seconds_to_ms = |seconds: f64| (seconds.max(0.0) * 1000.0).round() as u32;
+
+    let chapters: Vec<NewChapter> = match document {
+        ChapterDocument::Podlove(chapters) => chapters
+            .into_iter()
+            .map(|chapter| {
+                Ok(NewChapter {
+                    start: parse_chapter_time(&chapter.start)?,
+                    end: None,
+                    title: chapter.title,
+                    url: non_empty(chapter.href),
+                    image: local_image(chapter.image),
+                })
+            })
+            .collect::<Result<_, String>>()?,
+        ChapterDocument::Podcasting20 { chapters } => chapters
+            .into_iter()
+            .map(|chapter| NewChapter {
+                start: seconds_to_ms(chapter.start_time),
+                end: chapter.end_time.map(seconds_to_ms),
+                title: chapter.title.unwrap_or_default(),
+                url: non_empty(chapter.url),
+                image: local_image(chapter.img),
+            })
+            .collect(),
+    };
+
+    if chapters.is_empty() {
+        return Err("El JSON no contiene capítulos".to_string());
+    }
+    Ok(chapters)
+}
+
+/// Muestra los capítulos del tag por pantalla
+pub fn display_chapters(tag: &Tag) {
+    let chapters = sorted_chapters(tag);
+    if chapters.is_empty() {
+        println!("⚠️  El archivo no tiene capítulos");
+        return;
+    }
+
+    println!("📚 Capítulos: {}", chapters.len());
+    for (index, chapter) in chapters.iter().enumerate() {
+        let title = chapter
+            .frames
+            .iter()
+            .find(|frame| frame.id() == "TIT2")
+            .and_then(|frame| frame.content().text())
+            .unwrap_or("(sin título)");
+        let end = if chapter.end_time > chapter.start_time {
+            format_chapter_time(chapter.end_time)
+        } else {
+            "fin".to_string()
+        };
+        println!(
+            "   [{}] {} → {}  {} ({})",
+            index + 1,
+            format_chapter_time(chapter.start_time),
+            end,
+            title,
+            chapter.element_id
+        );
+        for frame in &chapter.frames {
+            match frame.content() {
+                Content::ExtendedLink(link) => println!("       🔗 {}", link.link),
+                Content::Link(link) => println!("       🔗 {}", link),
+                Content::Picture(picture) => println!(
+                    "       🖼️  {}, {} bytes",
+                    picture.mime_type,
+                    picture.data.len()
+                ),
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Expande una ruta de entrada a la lista de archivos MP3 que representa
+///
+/// * Un archivo existente se devuelve tal cual, sea cual sea su extensión.
+/// * Un directorio se expande a los archivos `.mp3` que contiene; con
+///   `recursive` también se recorren sus subdirectorios.
+/// * Un patrón glob (`*.mp3`, `temporada*/*.mp3`) se expande a los archivos
+///   que coincidan.
+///
+/// Los archivos de cada directorio o patrón se devuelven ordenados por ruta.
+///
+/// # Errores
+///
+/// Retorna un error si:
+/// * La ruta no existe y no es un patrón glob
+/// * El patrón glob no es válido o no coincide con ningún archivo
+pub fn expand_input(input: &Path, recursive: bool) -> Result<Vec<PathBuf>, String> {
+    if input.is_file() {
+        return Ok(vec![input.to_path_buf()]);
+    }
+
+    if input.is_dir() {
+        let mut files = Vec::new();
+        collect_mp3_files(input, recursive, &mut files)
+            .map_err(|e| format!("No se pudo leer el directorio '{}': {}", input.display(), e))?;
+        files.sort();
+        return Ok(files);
+    }
+
+    let pattern = input.to_string_lossy();
+    if !pattern.contains(['*', '?', '[']) {
+        return Err(format!("El archivo '{}' no existe", input.display()));
+    }
+
+    let entries =
+        glob::glob(&pattern).map_err(|e| format!("Patrón no válido '{}': {}", pattern, e))?;
+
+    let mut files = Vec::new();
+    for path in entries.flatten() {
+        if path.is_dir() {
+            collect_mp3_files(&path, recursive, &mut files).map_err(|e| {
+                format!("No se pudo leer el directorio '{}': {}", path.display(), e)
+            })?;
+        } else {
+            files.push(path);
+        }
+    }
+
+    if files.is_empty() {
+        return Err(format!("Ningún archivo coincide con '{}'", pattern));
+    }
+
+    files.sort();
+    Ok(files)
+}
+
+/// Criterio de orden para numerar las pistas de un directorio
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NumberOrder {
+    /// Nombre del archivo
+    Filename,
+    /// Fecha de modificación del archivo
+    Mtime,
+    /// Título existente (TIT2)
+    Title,
+    /// Fecha de grabación (TDRC) o año
+    Date,
+}
+
+/// Ordena los archivos según el criterio indicado para numerarlos
+///
+/// Los archivos sin título o fecha van al final. Los empates se resuelven
+/// por nombre de archivo.
+pub fn sort_for_numbering(files: &mut [PathBuf], order: NumberOrder) {
+    let key = |path: &PathBuf| -> Option<String> {
+        match order {
+            NumberOrder::Filename => None,
+            NumberOrder::Mtime => fs::metadata(path)
+                .and_then(|m| m.modified())
+                .ok()
+                .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+                .map(|d| format!("{:020}", d.as_nanos())),
+            NumberOrder::Title => Tag::read_from_path(path)
+                .ok()
+                .and_then(|tag| tag.title().map(str::to_lowercase)),
+            NumberOrder::Date => Tag::read_from_path(path).ok().and_then(|tag| {
+                tag.date_recorded()
+                    .map(|date| date.to_string())
+                    .or(tag.year().map(|year| year.to_string()))
+            }),
+        }
+    };
+
+    let mut keyed: Vec<(Option<String>, PathBuf)> =
+        files.iter().map(|path| (key(path), path.clone())).collect();
+    keyed.sort_by(|(a, path_a), (b, path_b)| match (a, b) {
+        (Some(a), Some(b)) => a.cmp(b).then_with(|| path_a.cmp(path_b)),
+        (Some(_), None) => std::cmp::Ordering::Less,
+        (None, Some(_)) => std::cmp::Ordering::Greater,
+        (None, None) => path_a.cmp(path_b),
+    });
+
+    for (slot, (_, path)) in files.iter_mut().zip(keyed) {
+        *slot = path;
+    }
+}
+
+/// Asigna el número de pista y el total (TRCK `3/12`)
+///
+/// # Retorna
+///
+/// `true` si el tag ha cambiado
+pub fn number_track(tag: &mut Tag, track: u32, total: u32) -> Result<bool, String> {
+    let before = (tag.track(), tag.total_tracks());
+    apply_metadata(
+        tag,
+        None,
+        &[],
+        None,
+        None,
+        None,
+        Some(track),
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+    );
+    apply_totals(tag, Some(track), Some(total), None, None)?;
+    Ok(before != (Some(track), Some(total)))
+}
+
+/// Añade a `files` los archivos `.mp3` del directorio (y subdirectorios si `recursive`)
+fn collect_mp3_files(dir: &Path, recursive: bool, files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            if recursive {
+                collect_mp3_files(&path, recursive, files)?;
+            }
+        } else if path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("mp3"))
+        {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests;
@@ -1,8 +1,10 @@
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
 use id3::Tag;
 use id3cli::*;
+use serde::Serialize;
+use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// CLI para añadir tags ID3 y carátulas a archivos MP3
 #[derive(Parser, Debug)]
@@ -11,357 +13,1985 @@ use std::path::PathBuf;
 struct Args {
     #[command(subcommand)]
     command: Command,
+
+    /// Versión ID3 con la que se escriben los tags (2.3, 2.4)
+    #[arg(long, global = true, value_enum, default_value_t = Id3VersionArg::V24)]
+    id3_version: Id3VersionArg,
+}
+
+/// Versión ID3 de escritura
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum Id3VersionArg {
+    /// ID3v2.3 (máxima compatibilidad con reproductores antiguos)
+    #[value(name = "2.3")]
+    V23,
+    /// ID3v2.4
+    #[value(name = "2.4")]
+    V24,
+}
+
+impl From<Id3VersionArg> for id3::Version {
+    fn from(version: Id3VersionArg) -> Self {
+        match version {
+            Id3VersionArg::V23 => id3::Version::Id3v23,
+            Id3VersionArg::V24 => id3::Version::Id3v24,
+        }
+    }
+}
+
+/// Formato de salida del comando show
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum OutputFormat {
+    /// Texto legible con emojis
+    Text,
+    /// Documento JSON con todos los frames
+    Json,
+}
+
+/// Formato de recodificación de carátulas
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum CoverFormatArg {
+    /// JPEG (con --cover-quality)
+    Jpeg,
+    /// PNG sin pérdida
+    Png,
+}
+
+impl From<CoverFormatArg> for CoverFormat {
+    fn from(format: CoverFormatArg) -> Self {
+        match format {
+            CoverFormatArg::Jpeg => CoverFormat::Jpeg,
+            CoverFormatArg::Png => CoverFormat::Png,
+        }
+    }
+}
+
+/// Criterio de orden del comando number
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum NumberOrderArg {
+    /// Nombre del archivo
+    Filename,
+    /// Fecha de modificación del archivo
+    Mtime,
+    /// Título existente (TIT2)
+    Title,
+    /// Fecha de grabación (TDRC) o año
+    Date,
+}
+
+impl From<NumberOrderArg> for NumberOrder {
+    fn from(order: NumberOrderArg) -> Self {
+        match order {
+            NumberOrderArg::Filename => NumberOrder::Filename,
+            NumberOrderArg::Mtime => NumberOrder::Mtime,
+            NumberOrderArg::Title => NumberOrder::Title,
+            NumberOrderArg::Date => NumberOrder::Date,
+        }
+    }
 }
 
 #[derive(Subcommand, Debug)]
-#[allow(clippy::large_enum_variant, clippy::too_many_arguments)]
+#[allow(clippy::large_enum_variant)]
 enum Command {
     /// Mostrar todos los tags del archivo MP3
     Show {
-        /// Ruta del archivo MP3
-        file: PathBuf,
+        /// Rutas de archivos MP3, directorios o patrones glob
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+
+        /// Recorrer los directorios de forma recursiva
+        #[arg(short, long)]
+        recursive: bool,
+
+        /// Formato de salida (text, json)
+        #[arg(short, long, value_enum, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
     },
     /// Editar tags ID3 del archivo MP3
-    Edit {
-        /// Ruta del archivo MP3
+    Edit(EditArgs),
+    /// Eliminar tags específicos del archivo MP3
+    Remove {
+        /// Ruta del archivo MP3, directorio o patrón glob
         file: PathBuf,
 
-        /// Título de la canción
+        /// Tags a eliminar (title, artist, album, year, genre, track, track_total, season, season_total, date, copyright, cover, cover:TIPO, cover:N, lyrics, synced_lyrics, url, podcast, category, long_description, keywords, episode_id, feed_url, comment, compilation, album_sort, artist_sort, title_sort, chapters, txxx:DESCRIPCIÓN, wxxx:DESCRIPCIÓN o un ID de frame como TKEY)
+        tags: Vec<String>,
+
+        /// Archivos, directorios o patrones glob adicionales (se puede especificar múltiples veces)
+        #[arg(short, long = "input")]
+        input: Vec<PathBuf>,
+
+        /// Recorrer los directorios de forma recursiva
         #[arg(short, long)]
-        title: Option<String>,
+        recursive: bool,
 
-        /// Artista (se puede especificar múltiples veces)
+        /// Eliminar todos los tags del archivo
         #[arg(short, long)]
-        artist: Vec<String>,
+        all: bool,
 
-        /// Álbum
-        #[arg(short = 'A', long)]
-        album: Option<String>,
+        /// Mostrar los frames que se eliminarían sin modificar el archivo
+        #[arg(short = 'n', long)]
+        dry_run: bool,
+    },
+    /// Renombrar archivos a partir de sus tags usando una plantilla
+    Rename {
+        /// Rutas de archivos MP3, directorios o patrones glob
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
 
-        /// Año
+        /// Plantilla de destino, p. ej. "{album_artist}/{album}/{track:02} - {title}.mp3"
         #[arg(short, long)]
-        year: Option<i32>,
+        template: String,
+
+        /// Directorio base de las rutas generadas (por defecto, el directorio de cada archivo)
+        #[arg(short = 'D', long)]
+        dest: Option<PathBuf>,
 
-        /// Género
+        /// Recorrer los directorios de forma recursiva
         #[arg(short, long)]
-        genre: Option<String>,
+        recursive: bool,
 
-        /// Número de pista
-        #[arg(short = 'T', long)]
-        track: Option<u32>,
+        /// Mostrar los movimientos previstos sin renombrar nada
+        #[arg(short = 'n', long)]
+        dry_run: bool,
+    },
+    /// Rellenar tags a partir del nombre del archivo y sus directorios
+    FromFilename {
+        /// Rutas de archivos MP3, directorios o patrones glob
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
 
-        /// Temporada (TPOS - útil para podcasts)
-        #[arg(short = 'S', long)]
-        season: Option<u32>,
+        /// Patrón de la ruta sin extensión, p. ej. "%artist% - %album%/%track% - %title%"
+        #[arg(short, long)]
+        pattern: String,
 
-        /// Fecha de grabación (YYYY-MM-DD o YYYY)
-        #[arg(short = 'd', long)]
-        date: Option<String>,
+        /// Recorrer los directorios de forma recursiva
+        #[arg(short, long)]
+        recursive: bool,
 
-        /// Copyright
-        #[arg(short = 'C', long)]
-        copyright: Option<String>,
+        /// Mostrar los cambios frame a frame sin modificar el archivo
+        #[arg(short = 'n', long)]
+        dry_run: bool,
+    },
+    /// Exportar los tags a un sidecar JSON (se escribe en stdout)
+    Export {
+        /// Ruta del archivo MP3
+        file: PathBuf,
 
-        /// Compositor (TCOM)
-        #[arg(long)]
-        composer: Option<String>,
+        /// Guardar las carátulas como archivos en este directorio en lugar de incrustarlas en base64
+        #[arg(short, long)]
+        pictures_dir: Option<PathBuf>,
+    },
+    /// Exportar la letra sincronizada (SYLT) a formato LRC
+    ExportLrc {
+        /// Ruta del archivo MP3
+        file: PathBuf,
 
-        /// Subtítulo o descripción (TIT3)
-        #[arg(long)]
-        subtitle: Option<String>,
+        /// Idioma de la letra a exportar (por defecto, la primera)
+        #[arg(long, value_parser = validate_language)]
+        lang: Option<String>,
 
-        /// Artista original (TOPE)
-        #[arg(long)]
-        original_artist: Option<String>,
+        /// Archivo de salida (por defecto, la salida estándar)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Importar los tags desde un sidecar JSON
+    Import {
+        /// Ruta del archivo MP3
+        file: PathBuf,
+
+        /// Ruta del sidecar JSON
+        sidecar: PathBuf,
 
-        /// Artista del álbum / Publisher (TPE2)
+        /// Eliminar todos los tags existentes antes de importar
         #[arg(long)]
-        album_artist: Option<String>,
+        replace: bool,
+
+        /// Mostrar los cambios frame a frame sin modificar el archivo
+        #[arg(short = 'n', long)]
+        dry_run: bool,
+    },
+    /// Gestionar los capítulos de un podcast (CHAP/CTOC)
+    Chapters {
+        #[command(subcommand)]
+        action: ChaptersCommand,
+    },
+    /// Convertir los tags entre ID3v2.3 e ID3v2.4
+    Convert {
+        /// Rutas de archivos MP3, directorios o patrones glob
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
+
+        /// Versión de destino (2.3, 2.4)
+        #[arg(long, value_enum)]
+        to: Id3VersionArg,
 
-        /// Ruta del archivo de imagen para la carátula (JPG, PNG, WEBP)
+        /// Recorrer los directorios de forma recursiva
         #[arg(short, long)]
-        cover: Option<PathBuf>,
+        recursive: bool,
 
-        /// Letra de la canción (lyrics)
-        #[arg(short = 'L', long)]
-        lyrics: Option<String>,
+        /// Mostrar los cambios frame a frame sin modificar el archivo
+        #[arg(short = 'n', long)]
+        dry_run: bool,
+    },
+    /// Revisar la calidad de los tags (código de salida 1 si hay problemas)
+    Lint {
+        /// Rutas de archivos MP3, directorios o patrones glob
+        #[arg(required = true)]
+        files: Vec<PathBuf>,
 
-        /// URL asociada (sitio web del artista, página oficial, etc.)
-        #[arg(short = 'u', long)]
-        url: Option<String>,
+        /// Recorrer los directorios de forma recursiva
+        #[arg(short, long)]
+        recursive: bool,
 
-        /// Marcar como compilación (Apple TCMP)
-        #[arg(long)]
-        compilation: bool,
+        /// Formato de salida (text, json)
+        #[arg(short, long, value_enum, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
 
-        /// Orden de clasificación del álbum (Apple TSOA)
+        /// Validar también la portada contra los requisitos de Apple Podcasts
         #[arg(long)]
-        album_sort: Option<String>,
+        cover_validate: bool,
+    },
+    /// Gestionar las carátulas (APIC)
+    Cover {
+        #[command(subcommand)]
+        action: CoverCommand,
+    },
+    /// Copiar los tags de un archivo a uno o varios archivos de destino
+    Copy {
+        /// Archivo MP3 de origen
+        source: PathBuf,
 
-        /// Orden de clasificación del artista (Apple TSOP)
-        #[arg(long)]
-        artist_sort: Option<String>,
+        /// Archivos MP3 de destino, directorios o patrones glob
+        #[arg(required = true)]
+        dest: Vec<PathBuf>,
 
-        /// Orden de clasificación del título (Apple TSOT)
-        #[arg(long)]
-        title_sort: Option<String>,
+        /// Copiar solo estos tags, separados por comas (title,album,cover...)
+        #[arg(long, value_delimiter = ',')]
+        only: Vec<String>,
+
+        /// No copiar estos tags, separados por comas (lyrics,chapters...)
+        #[arg(long, value_delimiter = ',')]
+        except: Vec<String>,
+
+        /// Recorrer los directorios de forma recursiva
+        #[arg(short, long)]
+        recursive: bool,
+
+        /// Mostrar los cambios frame a frame sin modificar los archivos
+        #[arg(short = 'n', long)]
+        dry_run: bool,
     },
-    /// Eliminar tags específicos del archivo MP3
-    Remove {
+    /// Numerar de forma consecutiva las pistas de un directorio (TRCK N/TOTAL)
+    Number {
+        /// Directorio con los archivos MP3
+        #[arg(short, long)]
+        dir: PathBuf,
+
+        /// Orden de numeración (filename, mtime, title, date)
+        #[arg(short, long, value_enum, default_value_t = NumberOrderArg::Filename)]
+        sort: NumberOrderArg,
+
+        /// Número de la primera pista
+        #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..))]
+        start: u32,
+
+        /// Mostrar la numeración prevista sin modificar los archivos
+        #[arg(short = 'n', long)]
+        dry_run: bool,
+    },
+}
+
+/// Acciones del comando cover
+#[derive(Subcommand, Debug)]
+enum CoverCommand {
+    /// Extraer las carátulas a archivos de imagen
+    Extract {
         /// Ruta del archivo MP3
         file: PathBuf,
 
-        /// Tags a eliminar (title, artist, album, year, genre, track, season, date, copyright, cover, lyrics, url, compilation, album_sort, artist_sort, title_sort)
-        tags: Vec<String>,
+        /// Archivo de salida (una carátula) o directorio (varias); por defecto, junto al MP3
+        #[arg(short, long)]
+        output: Option<PathBuf>,
 
-        /// Eliminar todos los tags del archivo
+        /// Tipo de carátula a extraer (front, back, artist... o all)
+        #[arg(long = "type", default_value = "front", value_parser = parse_picture_selector)]
+        picture_type: PictureSelector,
+
+        /// Sobrescribir los archivos que ya existan
+        #[arg(short, long)]
+        force: bool,
+    },
+}
+
+/// Acciones del comando chapters
+#[derive(Subcommand, Debug)]
+enum ChaptersCommand {
+    /// Listar los capítulos del archivo
+    List {
+        /// Ruta del archivo MP3
+        file: PathBuf,
+    },
+    /// Añadir un capítulo
+    Add {
+        /// Ruta del archivo MP3
+        file: PathBuf,
+
+        /// Inicio del capítulo (HH:MM:SS[.mmm])
+        #[arg(short, long, value_parser = parse_chapter_time)]
+        start: u32,
+
+        /// Fin del capítulo (por defecto, el inicio del siguiente)
+        #[arg(short, long, value_parser = parse_chapter_time)]
+        end: Option<u32>,
+
+        /// Título del capítulo
+        #[arg(short, long)]
+        title: String,
+
+        /// URL asociada al capítulo
+        #[arg(short, long)]
+        url: Option<String>,
+
+        /// Imagen del capítulo (JPG, PNG, WEBP, GIF, BMP)
         #[arg(short, long)]
+        image: Option<PathBuf>,
+
+        /// Mostrar los cambios frame a frame sin modificar el archivo
+        #[arg(short = 'n', long)]
+        dry_run: bool,
+    },
+    /// Eliminar capítulos por posición (1, 2...) o identificador (chp0...)
+    Remove {
+        /// Ruta del archivo MP3
+        file: PathBuf,
+
+        /// Capítulos a eliminar
+        #[arg(required_unless_present = "all")]
+        chapters: Vec<String>,
+
+        /// Eliminar todos los capítulos
+        #[arg(short, long, conflicts_with = "chapters")]
         all: bool,
+
+        /// Mostrar los cambios frame a frame sin modificar el archivo
+        #[arg(short = 'n', long)]
+        dry_run: bool,
+    },
+    /// Importar capítulos desde texto (HH:MM:SS Título) o JSON (Podlove, Podcasting 2.0)
+    Import {
+        /// Ruta del archivo MP3
+        file: PathBuf,
+
+        /// Archivo de capítulos (.json o texto; `-` para la entrada estándar)
+        chapters: PathBuf,
+
+        /// Eliminar los capítulos existentes antes de importar
+        #[arg(long)]
+        replace: bool,
+
+        /// Mostrar los cambios frame a frame sin modificar el archivo
+        #[arg(short = 'n', long)]
+        dry_run: bool,
     },
 }
 
-fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let args = Args::parse();
+/// Argumentos del comando edit
+#[derive(clap::Args, Debug, Clone)]
+#[command(group(clap::ArgGroup::new("cover_source").args(["cover", "cover_auto"])))]
+struct EditArgs {
+    /// Rutas de archivos MP3, directorios o patrones glob
+    #[arg(required = true)]
+    files: Vec<PathBuf>,
 
-    match &args.command {
-        Command::Show { file } => {
-            // Verificar que el archivo MP3 existe
-            if !file.exists() {
-                eprintln!("Error: El archivo '{}' no existe", file.display());
-                std::process::exit(1);
-            }
+    /// Recorrer los directorios de forma recursiva
+    #[arg(short, long)]
+    recursive: bool,
 
-            // Leer tag
-            let tag = match Tag::read_from_path(file) {
-                Ok(tag) => tag,
-                Err(_) => {
-                    eprintln!("⚠️  No se encontraron tags ID3 en '{}'", file.display());
-                    std::process::exit(0);
-                }
-            };
+    /// Mostrar los cambios frame a frame sin modificar el archivo
+    #[arg(short = 'n', long)]
+    dry_run: bool,
 
-            display_tags(&tag);
-        }
-        Command::Edit {
-            file,
-            title,
-            artist,
-            album,
-            year,
-            genre,
-            track,
-            season,
-            date,
-            copyright,
-            composer,
-            subtitle,
-            original_artist,
-            album_artist,
-            cover,
-            lyrics,
-            url,
-            compilation,
-            album_sort,
-            artist_sort,
-            title_sort,
-        } => {
-            // Verificar que el archivo MP3 existe
-            if !file.exists() {
-                eprintln!("Error: El archivo '{}' no existe", file.display());
-                std::process::exit(1);
-            }
+    /// Título de la canción
+    #[arg(short, long)]
+    title: Option<String>,
 
-            // Leer o crear tag
-            let mut tag = match Tag::read_from_path(file) {
-                Ok(tag) => {
-                    println!("Tags existentes encontrados en '{}'", file.display());
-                    tag
-                }
-                Err(_) => {
-                    println!("Creando nuevos tags para '{}'", file.display());
-                    Tag::new()
-                }
-            };
+    /// Artista (se puede especificar múltiples veces)
+    #[arg(short, long)]
+    artist: Vec<String>,
 
-            // Aplicar metadatos
-            let changed = apply_metadata(
-                &mut tag,
-                title.as_deref(),
-                artist,
-                album.as_deref(),
-                *year,
-                genre.as_deref(),
-                *track,
-                *season,
-                date.as_deref(),
-                copyright.as_deref(),
-                composer.as_deref(),
-                subtitle.as_deref(),
-                original_artist.as_deref(),
-                album_artist.as_deref(),
-            );
+    /// Álbum
+    #[arg(short = 'A', long)]
+    album: Option<String>,
 
-            // Imprimir cambios aplicados
-            if let Some(title) = title {
-                println!("✓ Título: {}", title);
-            }
-            if !artist.is_empty() {
-                println!("✓ Artista(s): {}", artist.join("; "));
-            }
-            if let Some(album) = album {
-                println!("✓ Álbum: {}", album);
-            }
-            if let Some(year) = year {
-                println!("✓ Año: {}", year);
-            }
-            if let Some(genre) = genre {
-                println!("✓ Género: {}", genre);
-            }
-            if let Some(track) = track {
-                println!("✓ Pista: {}", track);
-            }
-            if let Some(season) = season {
-                println!("✓ Temporada: {}", season);
-            }
-            if let Some(date) = date {
-                println!("✓ Fecha: {}", date);
-            }
-            if let Some(copyright) = copyright {
-                println!("✓ Copyright: {}", copyright);
-            }
-            if let Some(composer) = composer {
-                println!("✓ Compositor: {}", composer);
-            }
-            if let Some(subtitle) = subtitle {
-                println!("✓ Subtítulo: {}", subtitle);
-            }
-            if let Some(original_artist) = original_artist {
-                println!("✓ Artista original: {}", original_artist);
-            }
-            if let Some(album_artist) = album_artist {
-                println!("✓ Artista del álbum: {}", album_artist);
-            }
+    /// Año
+    #[arg(short, long)]
+    year: Option<i32>,
 
-            // Añadir lyrics
-            let mut lyrics_added = false;
-            if let Some(lyrics_text) = lyrics {
-                add_lyrics(&mut tag, lyrics_text);
-                let line_count = lyrics_text.lines().count();
-                println!("✓ Letra: {} línea(s)", line_count);
-                lyrics_added = true;
-            }
+    /// Género
+    #[arg(short, long)]
+    genre: Option<String>,
 
-            // Añadir URL
-            let mut url_added = false;
-            if let Some(url) = url {
-                add_url(&mut tag, url);
-                println!("✓ URL: {}", url);
-                url_added = true;
-            }
+    /// Número de pista, con total opcional (3 o 3/12)
+    #[arg(short = 'T', long, value_parser = parse_number_with_total)]
+    track: Option<(u32, Option<u32>)>,
 
-            // Añadir metadatos de Apple
-            let apple_added = add_apple_metadata(
-                &mut tag,
-                *compilation,
-                album_sort.as_deref(),
-                artist_sort.as_deref(),
-                title_sort.as_deref(),
-            );
+    /// Total de pistas (TRCK 3/12)
+    #[arg(long)]
+    track_total: Option<u32>,
 
-            if *compilation {
-                println!("✓ Compilación: Sí");
-            }
-            if let Some(sort) = album_sort {
-                println!("✓ Orden álbum: {}", sort);
-            }
-            if let Some(sort) = artist_sort {
-                println!("✓ Orden artista: {}", sort);
-            }
-            if let Some(sort) = title_sort {
-                println!("✓ Orden título: {}", sort);
-            }
+    /// Temporada (TPOS - útil para podcasts), con total opcional (1 o 1/3)
+    #[arg(short = 'S', long, value_parser = parse_number_with_total)]
+    season: Option<(u32, Option<u32>)>,
 
-            // Añadir carátula
-            let mut cover_added = false;
-            if let Some(cover_path) = cover {
-                if !cover_path.exists() {
-                    eprintln!(
-                        "Error: El archivo de carátula '{}' no existe",
-                        cover_path.display()
-                    );
-                    std::process::exit(1);
-                }
+    /// Total de temporadas o discos (TPOS 1/3)
+    #[arg(long)]
+    disc_total: Option<u32>,
 
-                let cover_data = fs::read(cover_path)?;
-                match add_cover_art(&mut tag, cover_path, cover_data) {
-                    Ok(_) => {
-                        println!("✓ Carátula añadida desde: {}", cover_path.display());
-                        cover_added = true;
-                    }
-                    Err(e) => {
-                        eprintln!("Error: {}", e);
-                        std::process::exit(1);
+    /// Fecha de grabación (YYYY-MM-DD o YYYY)
+    #[arg(short = 'd', long)]
+    date: Option<String>,
+
+    /// Copyright
+    #[arg(short = 'C', long)]
+    copyright: Option<String>,
+
+    /// Compositor (TCOM)
+    #[arg(long)]
+    composer: Option<String>,
+
+    /// Subtítulo o descripción (TIT3)
+    #[arg(long)]
+    subtitle: Option<String>,
+
+    /// Leer el subtítulo desde un archivo (`-` para la entrada estándar)
+    #[arg(long, value_name = "PATH", conflicts_with = "subtitle")]
+    subtitle_file: Option<PathBuf>,
+
+    /// Artista original (TOPE)
+    #[arg(long)]
+    original_artist: Option<String>,
+
+    /// Artista del álbum / Publisher (TPE2)
+    #[arg(long)]
+    album_artist: Option<String>,
+
+    /// Ruta del archivo de imagen para la carátula (JPG, PNG, WEBP, GIF, BMP)
+    #[arg(short, long)]
+    cover: Option<PathBuf>,
+
+    /// Buscar la carátula en el directorio de cada MP3 (cover.jpg, folder.png, front.*...)
+    #[arg(long)]
+    cover_auto: bool,
+
+    /// Nombres que busca --cover-auto en orden de preferencia, con o sin extensión
+    #[arg(
+        long,
+        value_delimiter = ',',
+        default_value = "cover,folder,front,album,artwork",
+        requires = "cover_auto"
+    )]
+    cover_names: Vec<String>,
+
+    /// Validar la carátula (--cover, --cover-auto o la ya incrustada) contra los requisitos de Apple Podcasts
+    #[arg(long)]
+    cover_validate: bool,
+
+    /// Tipo de imagen APIC de --cover (front, back, artist, leaflet, media...)
+    #[arg(long, default_value = "front", value_parser = validate_picture_type, requires = "cover_source")]
+    cover_type: id3::frame::PictureType,
+
+    /// Descripción de la imagen de --cover (por defecto, "Cover" en la portada)
+    #[arg(long, requires = "cover_source")]
+    cover_desc: Option<String>,
+
+    /// Reducir la carátula para que ningún lado supere estos píxeles
+    #[arg(long, value_name = "PX", requires = "cover_source", value_parser = clap::value_parser!(u32).range(1..))]
+    cover_max_size: Option<u32>,
+
+    /// Recodificar la carátula en este formato (jpeg, png)
+    #[arg(long, value_enum, requires = "cover_source")]
+    cover_format: Option<CoverFormatArg>,
+
+    /// Calidad JPEG de la carátula recodificada (1-100)
+    #[arg(long, default_value_t = 85, requires = "cover_source", value_parser = clap::value_parser!(u8).range(1..=100))]
+    cover_quality: u8,
+
+    /// Letra de la canción (lyrics)
+    #[arg(short = 'L', long)]
+    lyrics: Option<String>,
+
+    /// Leer la letra desde un archivo (`-` para la entrada estándar)
+    #[arg(long, value_name = "PATH", conflicts_with = "lyrics")]
+    lyrics_file: Option<PathBuf>,
+
+    /// Idioma de la letra (código ISO 639-2 de tres letras)
+    #[arg(long, default_value = "spa", value_parser = validate_language)]
+    lyrics_lang: String,
+
+    /// Descripción de la letra (permite varias letras por idioma)
+    #[arg(long, default_value = "")]
+    lyrics_desc: String,
+
+    /// Archivo LRC con la letra sincronizada (SYLT, usa --lyrics-lang y --lyrics-desc)
+    #[arg(long, value_name = "PATH")]
+    lrc: Option<PathBuf>,
+
+    /// Comentario (COMM)
+    #[arg(long)]
+    comment: Option<String>,
+
+    /// Leer el comentario desde un archivo (`-` para la entrada estándar)
+    #[arg(long, value_name = "PATH", conflicts_with = "comment")]
+    comment_file: Option<PathBuf>,
+
+    /// Idioma del comentario (código ISO 639-2 de tres letras)
+    #[arg(long, default_value = "spa", value_parser = validate_language)]
+    comment_lang: String,
+
+    /// Descripción del comentario (permite varios comentarios por idioma)
+    #[arg(long, default_value = "")]
+    comment_desc: String,
+
+    /// URL asociada (sitio web del artista, página oficial, etc.)
+    #[arg(short = 'u', long)]
+    url: Option<String>,
+
+    /// Marcar como compilación (Apple TCMP)
+    #[arg(long)]
+    compilation: bool,
+
+    /// Orden de clasificación del álbum (Apple TSOA)
+    #[arg(long)]
+    album_sort: Option<String>,
+
+    /// Orden de clasificación del artista (Apple TSOP)
+    #[arg(long)]
+    artist_sort: Option<String>,
+
+    /// Orden de clasificación del título (Apple TSOT)
+    #[arg(long)]
+    title_sort: Option<String>,
+
+    /// Marcar como episodio de podcast (iTunes PCST)
+    #[arg(long)]
+    podcast: bool,
+
+    /// Categoría del podcast (iTunes TCAT)
+    #[arg(long)]
+    category: Option<String>,
+
+    /// Descripción larga del episodio (iTunes TDES)
+    #[arg(long)]
+    long_description: Option<String>,
+
+    /// Leer la descripción larga desde un archivo (`-` para la entrada estándar)
+    #[arg(long, value_name = "PATH", conflicts_with = "long_description")]
+    long_description_file: Option<PathBuf>,
+
+    /// Palabras clave separadas por comas (iTunes TKWD)
+    #[arg(long)]
+    keywords: Option<String>,
+
+    /// Identificador único del episodio (iTunes TGID)
+    #[arg(long)]
+    episode_id: Option<String>,
+
+    /// URL del feed del podcast (iTunes WFED)
+    #[arg(long)]
+    feed_url: Option<String>,
+
+    /// Frame de texto definido por el usuario (TXXX, se puede repetir: --txxx "CATALOGO=AT-042")
+    #[arg(long, value_name = "DESCRIPCIÓN=VALOR", value_parser = parse_description_assignment)]
+    txxx: Vec<(String, String)>,
+
+    /// Frame de URL definido por el usuario (WXXX, se puede repetir: --wxxx "Donaciones=https://...")
+    #[arg(long, value_name = "DESCRIPCIÓN=URL", value_parser = parse_description_assignment)]
+    wxxx: Vec<(String, String)>,
+
+    /// Frame de texto o URL por su ID de ID3v2.4 (se puede repetir: --frame TKEY=Am)
+    #[arg(long = "frame", value_name = "ID=VALOR", value_parser = parse_frame_assignment)]
+    frames: Vec<(String, String)>,
+}
+
+/// Informe JSON de un archivo en `show --format json`
+#[derive(Serialize)]
+struct FileReport {
+    file: String,
+    #[serde(flatten)]
+    report: TagReport,
+}
+
+/// Resultado JSON de `lint` para un archivo
+#[derive(Serialize)]
+struct LintReport {
+    file: String,
+    issues: Vec<LintIssue>,
+}
+
+/// Expande todas las entradas a archivos, informando de las que fallan
+///
+/// Retorna los archivos encontrados y el número de entradas con error.
+fn collect_files(inputs: &[PathBuf], recursive: bool) -> (Vec<PathBuf>, usize) {
+    let mut files = Vec::new();
+    let mut errors = 0;
+
+    for input in inputs {
+        match expand_input(input, recursive) {
+            Ok(expanded) => {
+                for file in expanded {
+                    if !files.contains(&file) {
+                        files.push(file);
                     }
                 }
             }
-
-            // Guardar cambios
-            if changed || cover_added || lyrics_added || url_added || apple_added {
-                tag.write_to_path(file, id3::Version::Id3v24)?;
-                println!("\n✅ Tags guardados correctamente en '{}'", file.display());
-            } else {
-                println!("\n⚠️  No se especificaron cambios. Usa --help para ver las opciones.");
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                errors += 1;
             }
         }
-        Command::Remove { file, tags, all } => {
-            // Verificar que el archivo MP3 existe
-            if !file.exists() {
-                eprintln!("Error: El archivo '{}' no existe", file.display());
-                std::process::exit(1);
-            }
+    }
 
-            // Eliminar todos los tags
-            if *all {
-                let mut tag = match Tag::read_from_path(file) {
-                    Ok(tag) => tag,
+    (files, errors)
+}
+
+/// Imprime el resumen final de un procesamiento por lotes y termina con
+/// código de salida 1 si hubo algún error o no se encontró ningún archivo
+fn finish_batch(processed: usize, mut errors: usize, batch: bool) {
+    if processed == 0 && errors == 0 {
+        eprintln!("⚠️  No se encontraron archivos MP3");
+        errors += 1;
+    }
+    if batch {
+        println!(
+            "\n📊 Resumen: {} archivo(s) procesado(s), {} error(es)",
+            processed, errors
+        );
+    }
+    if errors > 0 {
+        std::process::exit(1);
+    }
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args = Args::parse();
+    let version: id3::Version = args.id3_version.into();
+
+    match &args.command {
+        Command::Show {
+            files,
+            recursive,
+            format,
+        } => {
+            let (paths, errors) = collect_files(files, *recursive);
+            let batch = paths.len() + errors > 1;
+            let mut reports = Vec::new();
+
+            for file in &paths {
+                // Leer tag
+                let tag = match Tag::read_from_path(file) {
+                    Ok(tag) => Some(tag),
                     Err(_) => {
-                        eprintln!("Error: No se encontraron tags ID3 en '{}'", file.display());
-                        std::process::exit(1);
+                        eprintln!("⚠️  No se encontraron tags ID3 en '{}'", file.display());
+                        None
                     }
                 };
 
-                remove_all_tags(&mut tag);
-                tag.write_to_path(file, id3::Version::Id3v24)?;
-                println!("\n✅ Todos los tags eliminados de '{}'", file.display());
-                return Ok(());
+                match format {
+                    OutputFormat::Text => {
+                        if batch {
+                            println!("\n📁 {}", file.display());
+                        }
+                        if let Some(tag) = &tag {
+                            display_tags(tag);
+                        }
+                    }
+                    OutputFormat::Json => reports.push(FileReport {
+                        file: file.display().to_string(),
+                        report: tag
+                            .as_ref()
+                            .map(|tag| tag_report(tag, read_tag_size(file)))
+                            .unwrap_or_default(),
+                    }),
+                }
             }
 
-            // Verificar que se especificaron tags para eliminar
-            if tags.is_empty() {
-                eprintln!("Error: Debes especificar al menos un tag para eliminar");
-                eprintln!("Ejemplo: id3cli remove cancion.mp3 title artist");
-                std::process::exit(1);
+            // Siempre un array, aunque solo haya un archivo, para que el
+            // formato no dependa de cuántos archivos coincidan
+            if *format == OutputFormat::Json {
+                println!("{}", serde_json::to_string_pretty(&reports)?);
             }
 
-            // Leer tag
-            let mut tag = match Tag::read_from_path(file) {
-                Ok(tag) => tag,
-                Err(_) => {
-                    eprintln!("Error: No se encontraron tags ID3 en '{}'", file.display());
+            finish_batch(paths.len(), errors, batch && *format == OutputFormat::Text);
+        }
+        Command::Edit(edit) => {
+            // Leer los textos largos una sola vez para todos los archivos
+            let edit = &match resolve_text_files(edit) {
+                Ok(edit) => edit,
+                Err(e) => {
+                    eprintln!("Error: {}", e);
                     std::process::exit(1);
                 }
             };
 
-            // Eliminar tags
-            let removed = remove_tags(&mut tag, tags);
-
-            if removed {
-                tag.write_to_path(file, id3::Version::Id3v24)?;
-                println!("\n✅ Tags eliminados correctamente de '{}'", file.display());
+            // Leer la letra sincronizada una sola vez para todos los archivos
+            let synced_lyrics = match &edit.lrc {
+                Some(path) => match read_text_source(path)
+                    .map_err(|e| e.to_string())
+                    .and_then(|text| parse_lrc(&text))
+                {
+                    Ok(lines) => lines,
+                    Err(e) => {
+                        eprintln!("Error: '{}': {}", path.display(), e);
+                        std::process::exit(1);
+                    }
+                },
+                None => Vec::new(),
+            };
+
+            // Leer la carátula una sola vez para todos los archivos
+            let cover_image = match &edit.cover {
+                Some(cover_path) => {
+                    if !cover_path.exists() {
+                        eprintln!(
+                            "Error: El archivo de carátula '{}' no existe",
+                            cover_path.display()
+                        );
+                        std::process::exit(1);
+                    }
+                    match prepare_cover(cover_path, edit) {
+                        Ok(cover) if edit.cover_validate => {
+                            if let Err(e) = check_apple_artwork(&cover.source, &cover.data) {
+                                eprintln!("Error: {}", e);
+                                std::process::exit(1);
+                            }
+                            Some(cover)
+                        }
+                        Ok(cover) => Some(cover),
+                        Err(e) => {
+                            eprintln!("Error: {}", e);
+                            std::process::exit(1);
+                        }
+                    }
+                }
+                None => None,
+            };
+            // Con --cover-auto, una carátula por directorio
+            let mut auto_covers: HashMap<PathBuf, Result<Option<CoverImage>, String>> =
+                HashMap::new();
+
+            let (paths, mut errors) = collect_files(&edit.files, edit.recursive);
+            let batch = paths.len() + errors > 1;
+
+            for file in &paths {
+                if batch {
+                    println!("\n📁 {}", file.display());
+                }
+                let cover = if edit.cover_auto {
+                    // Un nombre de archivo sin ruta tiene como padre ""
+                    let dir = match file.parent() {
+                        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+                        _ => PathBuf::from("."),
+                    };
+                    // El error se guarda para informar de él en cada archivo del directorio
+                    let cover = auto_covers
+                        .entry(dir.clone())
+                        .or_insert_with(|| discover_cover(&dir, edit).map_err(|e| e.to_string()));
+                    match cover {
+                        Ok(cover) => cover.as_ref(),
+                        Err(e) => {
+                            eprintln!("Error: {}", e);
+                            errors += 1;
+                            continue;
+                        }
+                    }
+                } else {
+                    cover_image.as_ref()
+                };
+                if let Err(e) = edit_file(file, edit, cover, &synced_lyrics, version) {
+                    eprintln!("Error: {}", e);
+                    errors += 1;
+                }
+            }
+
+            finish_batch(paths.len(), errors, batch);
+        }
+        Command::Remove {
+            file,
+            tags,
+            input,
+            recursive,
+            all,
+            dry_run,
+        } => {
+            // Verificar que se especificaron tags para eliminar
+            if !*all && tags.is_empty() {
+                eprintln!("Error: Debes especificar al menos un tag para eliminar");
+                eprintln!("Ejemplo: id3cli remove cancion.mp3 title artist");
+                std::process::exit(1);
+            }
+
+            let mut inputs = vec![file.clone()];
+            inputs.extend(input.iter().cloned());
+            let (paths, mut errors) = collect_files(&inputs, *recursive);
+            let batch = paths.len() + errors > 1;
+
+            for file in &paths {
+                if batch {
+                    println!("\n📁 {}", file.display());
+                }
+                if let Err(e) = remove_from_file(file, tags, *all, version, *dry_run) {
+                    eprintln!("Error: {}", e);
+                    errors += 1;
+                }
+            }
+
+            finish_batch(paths.len(), errors, batch);
+        }
+        Command::Rename {
+            files,
+            template,
+            dest,
+            recursive,
+            dry_run,
+        } => {
+            let (paths, mut errors) = collect_files(files, *recursive);
+            let batch = paths.len() + errors > 1;
+
+            // Calcular el destino de cada archivo
+            let mut moves = Vec::new();
+            for file in &paths {
+                let tag = match Tag::read_from_path(file) {
+                    Ok(tag) => tag,
+                    Err(_) => {
+                        eprintln!("Error: No se encontraron tags ID3 en '{}'", file.display());
+                        errors += 1;
+                        continue;
+                    }
+                };
+
+                match render_template(template, &tag) {
+                    Ok(relative) => {
+                        let base = match dest {
+                            Some(dest) => dest.clone(),
+                            None => file.parent().map(Path::to_path_buf).unwrap_or_default(),
+                        };
+                        moves.push((file.clone(), base.join(relative)));
+                    }
+                    Err(e) => {
+                        eprintln!("Error: '{}': {}", file.display(), e);
+                        errors += 1;
+                    }
+                }
+            }
+
+            let collisions = find_rename_collisions(&moves);
+            for (_, reason) in &collisions {
+                eprintln!("Error: {}", reason);
+            }
+            errors += collisions.len();
+
+            if *dry_run {
+                println!("🔍 Modo simulación: renombrados previstos (no se ha modificado nada)");
+            }
+
+            for (i, (from, to)) in moves.iter().enumerate() {
+                if collisions.iter().any(|(index, _)| *index == i) {
+                    continue;
+                }
+                if from == to {
+                    println!("= {} (sin cambios)", from.display());
+                    continue;
+                }
+                if *dry_run {
+                    println!("→ {} → {}", from.display(), to.display());
+                    continue;
+                }
+                match move_file(from, to) {
+                    Ok(()) => println!("✓ {} → {}", from.display(), to.display()),
+                    Err(e) => {
+                        eprintln!("Error: No se pudo mover '{}': {}", from.display(), e);
+                        errors += 1;
+                    }
+                }
+            }
+
+            finish_batch(paths.len(), errors, batch);
+        }
+        Command::FromFilename {
+            files,
+            pattern,
+            recursive,
+            dry_run,
+        } => {
+            let (paths, mut errors) = collect_files(files, *recursive);
+            let batch = paths.len() + errors > 1;
+
+            for file in &paths {
+                if batch {
+                    println!("\n📁 {}", file.display());
+                }
+                if let Err(e) = tag_from_filename(file, pattern, version, *dry_run) {
+                    eprintln!("Error: {}", e);
+                    errors += 1;
+                }
+            }
+
+            finish_batch(paths.len(), errors, batch);
+        }
+        Command::Export { file, pictures_dir } => {
+            // Verificar que el archivo MP3 existe
+            if !file.exists() {
+                eprintln!("Error: El archivo '{}' no existe", file.display());
+                std::process::exit(1);
+            }
+
+            let tag = match Tag::read_from_path(file) {
+                Ok(tag) => tag,
+                Err(_) => {
+                    eprintln!("Error: No se encontraron tags ID3 en '{}'", file.display());
+                    std::process::exit(1);
+                }
+            };
+
+            let mut sidecar = export_sidecar(&tag);
+            if let Some(dir) = pictures_dir {
+                let prefix = file
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy().to_string())
+                    .unwrap_or_else(|| "cover".to_string());
+                externalize_pictures(&mut sidecar, dir, &prefix)?;
+            }
+
+            println!("{}", sidecar.to_json()?);
+        }
+        Command::ExportLrc { file, lang, output } => {
+            // Verificar que el archivo MP3 existe
+            if !file.exists() {
+                eprintln!("Error: El archivo '{}' no existe", file.display());
+                std::process::exit(1);
+            }
+
+            let tag = Tag::read_from_path(file).unwrap_or_else(|_| Tag::new());
+            let Some(sylt) = tag
+                .synchronised_lyrics()
+                .find(|sylt| lang.as_ref().is_none_or(|lang| &sylt.lang == lang))
+            else {
+                eprintln!(
+                    "Error: No se encontró letra sincronizada (SYLT) en '{}'",
+                    file.display()
+                );
+                std::process::exit(1);
+            };
+
+            let lrc = match format_lrc(sylt) {
+                Ok(lrc) => lrc,
+                Err(e) => {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(1);
+                }
+            };
+
+            match output {
+                Some(path) => {
+                    fs::write(path, lrc)?;
+                    println!(
+                        "✅ Letra sincronizada [{}] exportada a '{}'",
+                        sylt.lang,
+                        path.display()
+                    );
+                }
+                None => print!("{}", lrc),
+            }
+        }
+        Command::Import {
+            file,
+            sidecar,
+            replace,
+            dry_run,
+        } => {
+            // Verificar que los archivos existen
+            for path in [file, sidecar] {
+                if !path.exists() {
+                    eprintln!("Error: El archivo '{}' no existe", path.display());
+                    std::process::exit(1);
+                }
+            }
+
+            let text = fs::read_to_string(sidecar)?;
+            let data = match TagSidecar::from_json(&text) {
+                Ok(data) => data,
+                Err(e) => {
+                    eprintln!("Error: Sidecar no válido '{}': {}", sidecar.display(), e);
+                    std::process::exit(1);
+                }
+            };
+
+            let before = Tag::read_from_path(file).unwrap_or_else(|_| Tag::new());
+            let mut tag = if *replace { Tag::new() } else { before.clone() };
+
+            let base_dir = sidecar.parent().unwrap_or_else(|| Path::new("."));
+            if let Err(e) = import_sidecar(&mut tag, &data, base_dir) {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+            let images = data.pictures.iter().chain(
+                data.chapters
+                    .iter()
+                    .filter_map(|chapter| chapter.image.as_ref()),
+            );
+            for image in images {
+                if let (Some(image_file), None) = (&image.file, &image.mime_type) {
+                    warn_image_mismatch(&base_dir.join(image_file));
+                }
+            }
+
+            if !save_tag(file, &before, &mut tag, version, *dry_run)? {
+                return Ok(());
+            }
+            println!(
+                "✅ Tags importados desde '{}' en '{}'",
+                sidecar.display(),
+                file.display()
+            );
+        }
+        Command::Lint {
+            files,
+            recursive,
+            format,
+            cover_validate,
+        } => {
+            let (paths, errors) = collect_files(files, *recursive);
+            let mut reports = Vec::new();
+
+            for file in &paths {
+                let issues = match Tag::read_from_path(file) {
+                    Ok(tag) => {
+                        let mut issues = lint_tag(&tag);
+                        if *cover_validate {
+                            issues.extend(lint_apple_artwork(&tag));
+                        }
+                        issues
+                    }
+                    Err(_) => vec![LintIssue {
+                        code: "no-tag",
+                        frame: None,
+                        message: "No se encontraron tags ID3".to_string(),
+                    }],
+                };
+                reports.push(LintReport {
+                    file: file.display().to_string(),
+                    issues,
+                });
+            }
+
+            let total: usize = reports.iter().map(|r| r.issues.len()).sum();
+            match format {
+                OutputFormat::Text => {
+                    for report in &reports {
+                        if report.issues.is_empty() {
+                            println!("✓ {}: sin problemas", report.file);
+                            continue;
+                        }
+                        println!("❌ {}: {} problema(s)", report.file, report.issues.len());
+                        for issue in &report.issues {
+                            match &issue.frame {
+                                Some(frame) => println!("   • [{}] {}", frame, issue.message),
+                                None => println!("   • {}", issue.message),
+                            }
+                        }
+                    }
+                    let failed = reports.iter().filter(|r| !r.issues.is_empty()).count();
+                    println!(
+                        "\n📊 Resumen: {} archivo(s) revisado(s), {} con problemas, {} problema(s)",
+                        reports.len(),
+                        failed,
+                        total
+                    );
+                }
+                OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&reports)?),
+            }
+
+            if paths.is_empty() && errors == 0 {
+                eprintln!("⚠️  No se encontraron archivos MP3");
+            }
+            if total > 0 || errors > 0 || paths.is_empty() {
+                std::process::exit(1);
+            }
+        }
+        Command::Cover { action } => match action {
+            CoverCommand::Extract {
+                file,
+                output,
+                picture_type,
+                force,
+            } => {
+                let tag = match Tag::read_from_path(file) {
+                    Ok(tag) => tag,
+                    Err(_) => {
+                        eprintln!("Error: No se encontraron tags ID3 en '{}'", file.display());
+                        std::process::exit(1);
+                    }
+                };
+
+                match extract_pictures(&tag, file, *picture_type, output.as_deref(), *force) {
+                    Ok(paths) => {
+                        for path in paths {
+                            println!("✓ Carátula extraída: {}", path.display());
+                        }
+                    }
+                    Err(e) => {
+                        eprintln!("Error: {}", e);
+                        std::process::exit(1);
+                    }
+                }
+            }
+        },
+        Command::Copy {
+            source,
+            dest,
+            only,
+            except,
+            recursive,
+            dry_run,
+        } => {
+            for name in only.iter().chain(except) {
+                if let Err(e) = field_frame_ids(name) {
+                    eprintln!("Error: {}", e);
+                    std::process::exit(1);
+                }
+            }
+
+            let source_tag = match Tag::read_from_path(source) {
+                Ok(tag) => tag,
+                Err(_) => {
+                    eprintln!(
+                        "Error: No se encontraron tags ID3 en '{}'",
+                        source.display()
+                    );
+                    std::process::exit(1);
+                }
+            };
+
+            let (mut paths, mut errors) = collect_files(dest, *recursive);
+            paths.retain(|path| path != source);
+            let batch = paths.len() + errors > 1;
+
+            for file in &paths {
+                if batch {
+                    println!("\n📁 {}", file.display());
+                }
+                if let Err(e) = copy_to_file(&source_tag, file, only, except, version, *dry_run) {
+                    eprintln!("Error: {}", e);
+                    errors += 1;
+                }
+            }
+
+            finish_batch(paths.len(), errors, batch);
+        }
+        Command::Number {
+            dir,
+            sort,
+            start,
+            dry_run,
+        } => {
+            if !dir.is_dir() {
+                eprintln!("Error: '{}' no es un directorio", dir.display());
+                std::process::exit(1);
+            }
+            let (mut paths, mut errors) = collect_files(std::slice::from_ref(dir), false);
+            sort_for_numbering(&mut paths, (*sort).into());
+
+            let total = *start + paths.len().saturating_sub(1) as u32;
+            for (i, file) in paths.iter().enumerate() {
+                let track = *start + i as u32;
+                if let Err(e) = number_file(file, track, total, version, *dry_run) {
+                    eprintln!("Error: '{}': {}", file.display(), e);
+                    errors += 1;
+                }
+            }
+
+            finish_batch(paths.len(), errors, true);
+        }
+        Command::Chapters { action } => {
+            if let Err(e) = chapters_command(action, version) {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+        }
+        Command::Convert {
+            files,
+            to,
+            recursive,
+            dry_run,
+        } => {
+            let (paths, mut errors) = collect_files(files, *recursive);
+            let batch = paths.len() + errors > 1;
+
+            for file in &paths {
+                if batch {
+                    println!("\n📁 {}", file.display());
+                }
+                if let Err(e) = convert_file(file, (*to).into(), *dry_run) {
+                    eprintln!("Error: {}", e);
+                    errors += 1;
+                }
+            }
+
+            finish_batch(paths.len(), errors, batch);
+        }
+    }
+
+    Ok(())
+}
+
+/// Sustituye las opciones `--*-file` por el texto que contienen
+///
+/// La entrada estándar solo se puede leer una vez, así que como mucho una
+/// de las opciones (incluida `--lrc`) puede ser `-`.
+fn resolve_text_files(args: &EditArgs) -> Result<EditArgs, Box<dyn std::error::Error>> {
+    let mut resolved = args.clone();
+    let sources = [
+        (&args.lyrics_file, &mut resolved.lyrics),
+        (&args.subtitle_file, &mut resolved.subtitle),
+        (&args.comment_file, &mut resolved.comment),
+        (&args.long_description_file, &mut resolved.long_description),
+    ];
+
+    let stdin_count = sources
+        .iter()
+        .map(|(path, _)| *path)
+        .chain([&args.lrc])
+        .filter(|path| path.as_deref() == Some(Path::new("-")))
+        .count();
+    if stdin_count > 1 {
+        return Err(
+            "Solo una opción --*-file o --lrc puede leer de la entrada estándar ('-')".into(),
+        );
+    }
+
+    for (path, target) in sources {
+        if let Some(path) = path {
+            *target = Some(read_text_source(path)?);
+        }
+    }
+
+    // Los archivos de texto suelen acabar en salto de línea, que en el
+    // subtítulo (TIT3) sobra: se descartan los saltos finales
+    if let Some(subtitle) = resolved
+        .subtitle
+        .as_mut()
+        .filter(|_| args.subtitle_file.is_some())
+    {
+        let len = subtitle.trim_end_matches('\n').len();
+        subtitle.truncate(len);
+    }
+
+    Ok(resolved)
+}
+
+/// Aplica los cambios del comando edit a un único archivo
+fn edit_file(
+    file: &Path,
+    args: &EditArgs,
+    cover: Option<&CoverImage>,
+    synced_lyrics: &[(u32, String)],
+    version: id3::Version,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Leer o crear tag
+    let mut tag = match Tag::read_from_path(file) {
+        Ok(tag) => {
+            println!("Tags existentes encontrados en '{}'", file.display());
+            tag
+        }
+        Err(_) => {
+            println!("Creando nuevos tags para '{}'", file.display());
+            Tag::new()
+        }
+    };
+    let before = tag.clone();
+
+    // Sin carátula nueva, se valida la que ya está incrustada
+    if args.cover_validate && cover.is_none() {
+        let picture = tag
+            .pictures()
+            .find(|p| p.picture_type == args.cover_type)
+            .or_else(|| tag.pictures().next())
+            .ok_or("El archivo no tiene carátula que validar")?;
+        check_apple_artwork(file, &picture.data)?;
+    }
+
+    // Aplicar metadatos
+    let changed = apply_metadata(
+        &mut tag,
+        args.title.as_deref(),
+        &args.artist,
+        args.album.as_deref(),
+        args.year,
+        args.genre.as_deref(),
+        args.track.map(|(track, _)| track),
+        args.season.map(|(season, _)| season),
+        args.date.as_deref(),
+        args.copyright.as_deref(),
+        args.composer.as_deref(),
+        args.subtitle.as_deref(),
+        args.original_artist.as_deref(),
+        args.album_artist.as_deref(),
+    );
+
+    // Los totales explícitos tienen prioridad sobre los de --track 3/12
+    let track_total = args.track_total.or(args.track.and_then(|(_, total)| total));
+    let disc_total = args.disc_total.or(args.season.and_then(|(_, total)| total));
+    let totals_changed = apply_totals(
+        &mut tag,
+        args.track.map(|(track, _)| track),
+        track_total,
+        args.season.map(|(season, _)| season),
+        disc_total,
+    )?;
+
+    // Imprimir cambios aplicados
+    if let Some(title) = &args.title {
+        println!("✓ Título: {}", title);
+    }
+    if !args.artist.is_empty() {
+        println!("✓ Artista(s): {}", args.artist.join("; "));
+    }
+    if let Some(album) = &args.album {
+        println!("✓ Álbum: {}", album);
+    }
+    if let Some(year) = args.year {
+        println!("✓ Año: {}", year);
+    }
+    if let Some(genre) = &args.genre {
+        println!("✓ Género: {}", genre);
+    }
+    if let Some(track) = args.track {
+        println!("✓ Pista: {}", track.0);
+    }
+    if let Some(total) = track_total {
+        println!("✓ Total de pistas: {}", total);
+    }
+    if let Some(season) = args.season {
+        println!("✓ Temporada: {}", season.0);
+    }
+    if let Some(total) = disc_total {
+        println!("✓ Total de temporadas: {}", total);
+    }
+    if let Some(date) = &args.date {
+        println!("✓ Fecha: {}", date);
+    }
+    if let Some(copyright) = &args.copyright {
+        println!("✓ Copyright: {}", copyright);
+    }
+    if let Some(composer) = &args.composer {
+        println!("✓ Compositor: {}", composer);
+    }
+    if let Some(subtitle) = &args.subtitle {
+        println!("✓ Subtítulo: {}", subtitle);
+    }
+    if let Some(original_artist) = &args.original_artist {
+        println!("✓ Artista original: {}", original_artist);
+    }
+    if let Some(album_artist) = &args.album_artist {
+        println!("✓ Artista del álbum: {}", album_artist);
+    }
+
+    // Añadir lyrics
+    let mut lyrics_added = false;
+    if let Some(lyrics_text) = &args.lyrics {
+        add_lyrics(&mut tag, lyrics_text, &args.lyrics_lang, &args.lyrics_desc);
+        let line_count = lyrics_text.lines().count();
+        println!("✓ Letra [{}]: {} línea(s)", args.lyrics_lang, line_count);
+        lyrics_added = true;
+    }
+
+    // Añadir letra sincronizada
+    if !synced_lyrics.is_empty() {
+        add_synced_lyrics(
+            &mut tag,
+            synced_lyrics,
+            &args.lyrics_lang,
+            &args.lyrics_desc,
+        );
+        println!(
+            "✓ Letra sincronizada [{}]: {} línea(s)",
+            args.lyrics_lang,
+            synced_lyrics.len()
+        );
+        lyrics_added = true;
+    }
+
+    // Añadir URL
+    let mut url_added = false;
+    if let Some(url) = &args.url {
+        add_url(&mut tag, url);
+        println!("✓ URL: {}", url);
+        url_added = true;
+    }
+
+    // Añadir metadatos de Apple
+    let apple_added = add_apple_metadata(
+        &mut tag,
+        args.compilation,
+        args.album_sort.as_deref(),
+        args.artist_sort.as_deref(),
+        args.title_sort.as_deref(),
+    );
+
+    if args.compilation {
+        println!("✓ Compilación: Sí");
+    }
+    if let Some(sort) = &args.album_sort {
+        println!("✓ Orden álbum: {}", sort);
+    }
+    if let Some(sort) = &args.artist_sort {
+        println!("✓ Orden artista: {}", sort);
+    }
+    if let Some(sort) = &args.title_sort {
+        println!("✓ Orden título: {}", sort);
+    }
+
+    // Añadir metadatos de podcast de iTunes
+    let podcast_added = add_podcast_metadata(
+        &mut tag,
+        &PodcastMetadata {
+            podcast: args.podcast,
+            category: args.category.as_deref(),
+            description: args.long_description.as_deref(),
+            keywords: args.keywords.as_deref(),
+            episode_id: args.episode_id.as_deref(),
+            feed_url: args.feed_url.as_deref(),
+        },
+    );
+
+    if args.podcast {
+        println!("✓ Podcast: Sí");
+    }
+    if let Some(category) = &args.category {
+        println!("✓ Categoría: {}", category);
+    }
+    if let Some(description) = &args.long_description {
+        println!(
+            "✓ Descripción larga: {} línea(s)",
+            description.lines().count()
+        );
+    }
+    if let Some(keywords) = &args.keywords {
+        println!("✓ Palabras clave: {}", keywords);
+    }
+    if let Some(episode_id) = &args.episode_id {
+        println!("✓ ID del episodio: {}", episode_id);
+    }
+    if let Some(feed_url) = &args.feed_url {
+        println!("✓ Feed: {}", feed_url);
+    }
+
+    // Añadir comentario
+    let mut frames_added = false;
+    if let Some(comment) = &args.comment {
+        frames_added |= add_comment(&mut tag, comment, &args.comment_lang, &args.comment_desc);
+        println!("✓ Comentario [{}]: {}", args.comment_lang, comment);
+    }
+
+    // Frames definidos por el usuario
+    for (description, value) in &args.txxx {
+        frames_added |= add_user_text(&mut tag, description, value);
+        println!("✓ TXXX {}: {}", description, value);
+    }
+    for (description, url) in &args.wxxx {
+        frames_added |= add_user_url(&mut tag, description, url);
+        println!("✓ WXXX {}: {}", description, url);
+    }
+
+    // Frames genéricos por ID
+    for (id, value) in &args.frames {
+        set_raw_frame(&mut tag, id, value)?;
+        println!("✓ {}: {}", id, value);
+        frames_added = true;
+    }
+
+    // Añadir carátula
+    let mut cover_added = false;
+    if let Some(cover) = cover {
+        let description = match (&args.cover_desc, args.cover_type) {
+            (Some(description), _) => description.as_str(),
+            (None, id3::frame::PictureType::CoverFront) => "Cover",
+            (None, _) => "",
+        };
+        let replaced = add_picture(
+            &mut tag,
+            cover.mime_type,
+            cover.data.clone(),
+            args.cover_type,
+            description,
+        );
+        let action = if replaced { "sustituida" } else { "añadida" };
+        println!(
+            "✓ Carátula ({}) {} desde: {}",
+            picture_type_name(args.cover_type),
+            action,
+            cover.source.display()
+        );
+        cover_added = true;
+    }
+
+    // Guardar cambios
+    if changed
+        || totals_changed
+        || cover_added
+        || lyrics_added
+        || url_added
+        || apple_added
+        || podcast_added
+        || frames_added
+    {
+        if !save_tag(file, &before, &mut tag, version, args.dry_run)? {
+            return Ok(());
+        }
+        println!("\n✅ Tags guardados correctamente en '{}'", file.display());
+    } else {
+        println!("\n⚠️  No se especificaron cambios. Usa --help para ver las opciones.");
+    }
+
+    Ok(())
+}
+
+/// Carátula leída (y procesada) lista para incrustar
+#[derive(Debug)]
+struct CoverImage {
+    /// Archivo de imagen original
+    source: PathBuf,
+    /// Tipo MIME del contenido (el del nuevo formato si se ha recodificado)
+    mime_type: &'static str,
+    data: Vec<u8>,
+}
+
+/// Busca la carátula de un directorio para `--cover-auto` y la prepara
+fn discover_cover(
+    dir: &Path,
+    args: &EditArgs,
+) -> Result<Option<CoverImage>, Box<dyn std::error::Error>> {
+    match find_cover_image(dir, &args.cover_names) {
+        Some(path) => {
+            println!("🔍 Carátula encontrada: {}", path.display());
+            let cover = prepare_cover(&path, args)?;
+            if args.cover_validate {
+                check_apple_artwork(&cover.source, &cover.data)?;
+            }
+            Ok(Some(cover))
+        }
+        None => {
+            println!(
+                "⚠️  No se encontró carátula en '{}' ({})",
+                dir.display(),
+                args.cover_names.join(", ")
+            );
+            Ok(None)
+        }
+    }
+}
+
+/// Valida la portada (o la primera imagen) del tag para `lint --cover-validate`
+fn lint_apple_artwork(tag: &Tag) -> Vec<LintIssue> {
+    let picture = tag
+        .pictures()
+        .find(|p| p.picture_type == id3::frame::PictureType::CoverFront)
+        .or_else(|| tag.pictures().next());
+
+    let problems = match picture {
+        Some(picture) => validate_apple_artwork(&picture.data),
+        None => vec!["No hay carátula".to_string()],
+    };
+    problems
+        .into_iter()
+        .map(|message| LintIssue {
+            code: "apple-artwork",
+            frame: Some("APIC".to_string()),
+            message,
+        })
+        .collect()
+}
+
+/// Comprueba una carátula con [`validate_apple_artwork`] e imprime el
+/// resultado
+///
+/// Retorna un error si la imagen no cumple algún requisito.
+fn check_apple_artwork(source: &Path, data: &[u8]) -> Result<(), String> {
+    let problems = validate_apple_artwork(data);
+    if problems.is_empty() {
+        println!(
+            "✓ Carátula válida para Apple Podcasts: {}",
+            source.display()
+        );
+        return Ok(());
+    }
+
+    for problem in &problems {
+        eprintln!("⚠️  {}", problem);
+    }
+    Err(format!(
+        "La carátula de '{}' no cumple los requisitos de Apple Podcasts ({} problema(s))",
+        source.display(),
+        problems.len()
+    ))
+}
+
+/// Avisa si el contenido de una imagen no corresponde a su extensión (los
+/// errores de lectura ya los informa quien la usa)
+fn warn_image_mismatch(path: &Path) {
+    if let Ok(data) = fs::read(path)
+        && let Some(warning) = image_mime_mismatch(path, &data)
+    {
+        eprintln!("⚠️  {}", warning);
+    }
+}
+
+/// Lee la carátula y la redimensiona y recodifica si se pidió con
+/// `--cover-max-size` o `--cover-format`, informando del tamaño antes y después
+fn prepare_cover(path: &Path, args: &EditArgs) -> Result<CoverImage, Box<dyn std::error::Error>> {
+    let data = fs::read(path)?;
+    if let Some(warning) = image_mime_mismatch(path, &data) {
+        eprintln!("⚠️  {}", warning);
+    }
+    if args.cover_max_size.is_none() && args.cover_format.is_none() {
+        return Ok(CoverImage {
+            source: path.to_path_buf(),
+            mime_type: image_mime_type(path, &data)?,
+            data,
+        });
+    }
+
+    let options = CoverProcessing {
+        max_size: args.cover_max_size,
+        format: args.cover_format.map(Into::into),
+        quality: args.cover_quality,
+    };
+    let processed = process_cover(&data, &options)?;
+
+    let (width, height) = processed.original_dimensions;
+    let (new_width, new_height) = processed.dimensions;
+    if processed.data == data {
+        println!(
+            "✓ Carátula sin cambios: {}×{} ({}), {}",
+            width,
+            height,
+            processed.mime_type,
+            format_bytes(data.len())
+        );
+    } else {
+        println!(
+            "✓ Carátula procesada: {}×{} → {}×{} ({}), {} → {}",
+            width,
+            height,
+            new_width,
+            new_height,
+            processed.mime_type,
+            format_bytes(data.len()),
+            format_bytes(processed.data.len())
+        );
+    }
+
+    Ok(CoverImage {
+        source: path.to_path_buf(),
+        mime_type: processed.mime_type,
+        data: processed.data,
+    })
+}
+
+/// Rellena los tags de un único archivo a partir de su ruta
+fn tag_from_filename(
+    file: &Path,
+    pattern: &str,
+    version: id3::Version,
+    dry_run: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let fields = parse_filename(file, pattern)?;
+
+    let mut tag = Tag::read_from_path(file).unwrap_or_else(|_| Tag::new());
+    let before = tag.clone();
+
+    for (field, value) in &fields {
+        println!("✓ {}: {}", field, value);
+    }
+
+    if !apply_filename_fields(&mut tag, &fields)? {
+        println!("\n⚠️  El patrón no contiene campos que aplicar.");
+        return Ok(());
+    }
+
+    if !save_tag(file, &before, &mut tag, version, dry_run)? {
+        return Ok(());
+    }
+    println!("\n✅ Tags guardados correctamente en '{}'", file.display());
+    Ok(())
+}
+
+/// Copia los frames seleccionados del tag de origen a un único archivo
+fn copy_to_file(
+    source: &Tag,
+    file: &Path,
+    only: &[String],
+    except: &[String],
+    version: id3::Version,
+    dry_run: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut tag = Tag::read_from_path(file).unwrap_or_else(|_| Tag::new());
+    let before = tag.clone();
+
+    let copied = copy_tags(source, &mut tag, only, except)?;
+    if copied == 0 {
+        println!("⚠️  No hay frames que copiar.");
+        return Ok(());
+    }
+    println!("✓ {} frame(s) copiado(s)", copied);
+
+    if !save_tag(file, &before, &mut tag, version, dry_run)? {
+        return Ok(());
+    }
+    println!("\n✅ Tags guardados correctamente en '{}'", file.display());
+    Ok(())
+}
+
+/// Asigna el número de pista y el total a un único archivo
+fn number_file(
+    file: &Path,
+    track: u32,
+    total: u32,
+    version: id3::Version,
+    dry_run: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut tag = Tag::read_from_path(file).unwrap_or_else(|_| Tag::new());
+    let before = tag.clone();
+
+    if !number_track(&mut tag, track, total)? {
+        println!("= {}: {}/{} (sin cambios)", file.display(), track, total);
+        return Ok(());
+    }
+
+    if save_tag(file, &before, &mut tag, version, dry_run)? {
+        println!("✓ {}: {}/{}", file.display(), track, total);
+    }
+    Ok(())
+}
+
+/// Guarda el tag con la versión indicada, convirtiendo los frames si se
+/// escribe en ID3v2.3, o muestra el diff si es una simulación
+///
+/// Retorna `true` si el archivo se ha escrito.
+fn save_tag(
+    file: &Path,
+    before: &Tag,
+    tag: &mut Tag,
+    version: id3::Version,
+    dry_run: bool,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    if version == id3::Version::Id3v23 {
+        for warning in convert_tag(tag, version).lossy {
+            println!("⚠️  {}", warning);
+        }
+    }
+
+    if dry_run {
+        print_dry_run(file, before, tag);
+        return Ok(false);
+    }
+
+    tag.write_to_path(file, version)?;
+    Ok(true)
+}
+
+/// Convierte los tags de un único archivo a la versión indicada
+fn convert_file(
+    file: &Path,
+    version: id3::Version,
+    dry_run: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut tag = Tag::read_from_path(file)
+        .map_err(|_| format!("No se encontraron tags ID3 en '{}'", file.display()))?;
+    let before = tag.clone();
+
+    let report = convert_tag(&mut tag, version);
+    for change in &report.changes {
+        println!("✓ {}", change);
+    }
+    for warning in &report.lossy {
+        println!("⚠️  {}", warning);
+    }
+
+    if dry_run {
+        print_dry_run(file, &before, &tag);
+        return Ok(());
+    }
+
+    tag.write_to_path(file, version)?;
+    println!(
+        "\n✅ Tags convertidos de {} a {} en '{}'",
+        before.version(),
+        version,
+        file.display()
+    );
+    Ok(())
+}
+
+/// Ejecuta una acción del comando chapters
+fn chapters_command(
+    action: &ChaptersCommand,
+    version: id3::Version,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let file = match action {
+        ChaptersCommand::List { file }
+        | ChaptersCommand::Add { file, .. }
+        | ChaptersCommand::Remove { file, .. }
+        | ChaptersCommand::Import { file, .. } => file,
+    };
+    if !file.exists() {
+        return Err(format!("El archivo '{}' no existe", file.display()).into());
+    }
+
+    let mut tag = Tag::read_from_path(file).unwrap_or_else(|_| Tag::new());
+    let before = tag.clone();
+
+    let dry_run = match action {
+        ChaptersCommand::List { .. } => {
+            display_chapters(&tag);
+            return Ok(());
+        }
+        ChaptersCommand::Add {
+            start,
+            end,
+            title,
+            url,
+            image,
+            dry_run,
+            ..
+        } => {
+            let chapter = NewChapter {
+                start: *start,
+                end: *end,
+                title: title.clone(),
+                url: url.clone(),
+                image: image.clone(),
+            };
+            add_chapter(&mut tag, &chapter)?;
+            if let Some(image) = &chapter.image {
+                warn_image_mismatch(image);
+            }
+            println!(
+                "✓ Capítulo añadido: {} {}",
+                format_chapter_time(chapter.start),
+                chapter.title
+            );
+            *dry_run
+        }
+        ChaptersCommand::Remove {
+            chapters,
+            all,
+            dry_run,
+            ..
+        } => {
+            if *all {
+                let count = remove_all_chapters(&mut tag);
+                println!("✓ Eliminados {} capítulo(s)", count);
             } else {
-                println!("\n⚠️  No se encontraron tags válidos para eliminar.");
+                for chapter in remove_chapters(&mut tag, chapters)? {
+                    println!(
+                        "✓ Capítulo eliminado: {} ({})",
+                        format_chapter_time(chapter.start_time),
+                        chapter.element_id
+                    );
+                }
             }
+            *dry_run
+        }
+        ChaptersCommand::Import {
+            chapters,
+            replace,
+            dry_run,
+            ..
+        } => {
+            let text = read_text_source(chapters)?;
+            let base_dir = chapters.parent().unwrap_or_else(|| Path::new("."));
+            let is_json = chapters
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+            let new_chapters = if is_json {
+                parse_chapter_json(&text, base_dir)
+            } else {
+                parse_chapter_list(&text)
+            }
+            .map_err(|e| format!("'{}': {}", chapters.display(), e))?;
+
+            if *replace {
+                remove_all_chapters(&mut tag);
+            }
+            for chapter in &new_chapters {
+                add_chapter(&mut tag, chapter)?;
+                if let Some(image) = &chapter.image {
+                    warn_image_mismatch(image);
+                }
+                println!("✓ {} {}", format_chapter_time(chapter.start), chapter.title);
+            }
+            println!("✓ Importados {} capítulo(s)", new_chapters.len());
+            *dry_run
+        }
+    };
+
+    if rebuild_chapters(&mut tag) {
+        println!("⚠️  El último capítulo no tiene fin: usa --end o define la duración (TLEN)");
+    }
+
+    if save_tag(file, &before, &mut tag, version, dry_run)? {
+        println!(
+            "\n✅ Capítulos guardados correctamente en '{}'",
+            file.display()
+        );
+    }
+    Ok(())
+}
+
+/// Muestra los cambios que se aplicarían al archivo sin modificarlo
+fn print_dry_run(file: &Path, before: &Tag, after: &Tag) {
+    println!(
+        "\n🔍 Modo simulación: cambios previstos en '{}' (no se ha modificado)",
+        file.display()
+    );
+    display_diff(&diff_tags(before, after));
+}
+
+/// Elimina los tags indicados (o todos si `all`) de un único archivo
+fn remove_from_file(
+    file: &Path,
+    tags: &[String],
+    all: bool,
+    version: id3::Version,
+    dry_run: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Leer tag
+    let mut tag = Tag::read_from_path(file)
+        .map_err(|_| format!("No se encontraron tags ID3 en '{}'", file.display()))?;
+    let before = tag.clone();
+
+    // Eliminar todos los tags
+    if all {
+        remove_all_tags(&mut tag);
+        if !save_tag(file, &before, &mut tag, version, dry_run)? {
+            return Ok(());
+        }
+        println!("\n✅ Todos los tags eliminados de '{}'", file.display());
+        return Ok(());
+    }
+
+    // Eliminar tags
+    let removed = remove_tags(&mut tag, tags);
+
+    if removed {
+        if !save_tag(file, &before, &mut tag, version, dry_run)? {
+            return Ok(());
         }
+        println!("\n✅ Tags eliminados correctamente de '{}'", file.display());
+    } else {
+        println!("\n⚠️  No se encontraron tags válidos para eliminar.");
     }
 
     Ok(())
@@ -1,7 +1,7 @@
 // Tests para la librería id3cli
 use super::*;
-use id3::frame::{Content, PictureType};
-use id3::{Tag, TagLike};
+use id3::frame::{Content, Picture, PictureType};
+use id3::{Frame, Tag, TagLike};
 use std::path::Path;
 
 #[test]
@@ -602,7 +602,7 @@ fn test_detect_mime_type_webp() {
 
 #[test]
 fn test_detect_mime_type_unsupported() {
-    let result = detect_mime_type(Path::new("test.gif"));
+    let result = detect_mime_type(Path::new("test.tiff"));
     assert!(result.is_err());
     assert!(result.unwrap_err().contains("no soportado"));
 }
@@ -640,7 +640,7 @@ fn test_add_lyrics() {
     let mut tag = Tag::new();
     let lyrics_text = "Primera línea\nSegunda línea\nTercera línea";
 
-    let result = add_lyrics(&mut tag, lyrics_text);
+    let result = add_lyrics(&mut tag, lyrics_text, "spa", "");
     assert!(result);
 
     // Verificar que se añadió el frame de lyrics
@@ -660,7 +660,7 @@ fn test_add_lyrics() {
 fn test_remove_lyrics() {
     let mut tag = Tag::new();
     let lyrics_text = "Test lyrics";
-    add_lyrics(&mut tag, lyrics_text);
+    add_lyrics(&mut tag, lyrics_text, "spa", "");
 
     // Verificar que se añadió
     let has_lyrics = tag
@@ -682,7 +682,7 @@ fn test_remove_lyrics() {
 #[test]
 fn test_remove_lyrics_spanish() {
     let mut tag = Tag::new();
-    add_lyrics(&mut tag, "Test");
+    add_lyrics(&mut tag, "Test", "spa", "");
 
     let changed = remove_tags(&mut tag, &["letra".to_string()]);
     assert!(changed);
@@ -1120,7 +1120,7 @@ fn test_remove_all_tags_function() {
     let data = vec![0xFF, 0xD8, 0xFF, 0xE0];
     let path = Path::new("test.jpg");
     add_cover_art(&mut tag, path, data).unwrap();
-    add_lyrics(&mut tag, "Letra de la canción");
+    add_lyrics(&mut tag, "Letra de la canción", "spa", "");
 
     assert_eq!(tag.title(), Some("Title"));
     assert_eq!(tag.pictures().count(), 1);
@@ -1142,3 +1142,2242 @@ fn test_remove_all_tags_function() {
         .any(|f| matches!(f.content(), Content::Lyrics(_)));
     assert!(!has_lyrics);
 }
+
+#[test]
+fn test_tag_report_empty() {
+    let tag = Tag::new();
+    let report = tag_report(&tag, None);
+
+    assert_eq!(report.version.as_deref(), Some("ID3v2.4"));
+    assert_eq!(report.size, None);
+    assert!(report.frames.is_empty());
+}
+
+#[test]
+fn test_tag_report_frames() {
+    let mut tag = Tag::new();
+    tag.set_title("Title");
+    add_lyrics(&mut tag, "Line 1\nLine 2", "spa", "");
+    add_url(&mut tag, "https://example.com");
+    add_cover_art(
+        &mut tag,
+        Path::new("cover.png"),
+        vec![0x89, 0x50, 0x4E, 0x47],
+    )
+    .unwrap();
+
+    let report = tag_report(&tag, Some(128));
+    assert_eq!(report.frames.len(), 4);
+    assert_eq!(report.size, Some(128));
+
+    let ids: Vec<&str> = report.frames.iter().map(|f| f.id.as_str()).collect();
+    assert_eq!(ids, vec!["TIT2", "USLT", "WOAR", "APIC"]);
+
+    match &report.frames[1].content {
+        FrameContent::Lyrics { lang, text, .. } => {
+            assert_eq!(lang, "spa");
+            assert_eq!(text, "Line 1\nLine 2");
+        }
+        other => panic!("Contenido inesperado: {:?}", other),
+    }
+
+    match &report.frames[3].content {
+        FrameContent::Picture {
+            mime_type, size, ..
+        } => {
+            assert_eq!(mime_type, "image/png");
+            assert_eq!(*size, 4);
+        }
+        other => panic!("Contenido inesperado: {:?}", other),
+    }
+}
+
+#[test]
+fn test_tag_report_json() {
+    let mut tag = Tag::new();
+    tag.set_title("Título");
+
+    let json = serde_json::to_value(tag_report(&tag, None)).unwrap();
+    assert_eq!(json["version"], "ID3v2.4");
+    assert!(json["size"].is_null());
+    assert_eq!(json["frames"][0]["id"], "TIT2");
+    assert_eq!(json["frames"][0]["content"]["type"], "text");
+    assert_eq!(json["frames"][0]["content"]["text"], "Título");
+}
+
+#[test]
+fn test_tag_report_podcast_flag() {
+    let mut tag = Tag::new();
+    add_podcast_metadata(
+        &mut tag,
+        &PodcastMetadata {
+            podcast: true,
+            ..Default::default()
+        },
+    );
+
+    let json = serde_json::to_value(tag_report(&tag, None)).unwrap();
+    assert_eq!(json["frames"][0]["id"], "PCST");
+    assert_eq!(json["frames"][0]["content"]["type"], "podcast");
+    assert_eq!(json["frames"][0]["content"]["podcast"], true);
+}
+
+#[test]
+fn test_read_tag_size() {
+    let dir = create_temp_dir("tag-size");
+    let file = dir.join("episodio.mp3");
+
+    // Cabecera ID3v2.2 con tamaño synchsafe 0x01 0x7F = 255 bytes
+    let mut data = b"ID3\x02\x00\x00\x00\x00\x01\x7F".to_vec();
+    data.resize(10 + 255 + 4, 0);
+    std::fs::write(&file, &data).unwrap();
+    assert_eq!(read_tag_size(&file), Some(265));
+
+    // ID3v2.4 con pie: 10 bytes más
+    data[3] = 4;
+    data[5] = 0x10;
+    std::fs::write(&file, &data).unwrap();
+    assert_eq!(read_tag_size(&file), Some(275));
+
+    std::fs::write(&file, b"sin tag").unwrap();
+    assert_eq!(read_tag_size(&file), None);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+/// Crea un directorio temporal único para tests de expansión de rutas
+fn create_temp_dir(name: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "id3cli_{}_{}",
+        name,
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos()
+    ));
+    std::fs::create_dir_all(dir.join("sub")).unwrap();
+    for file in ["b.mp3", "a.MP3", "notes.txt", "sub/c.mp3"] {
+        std::fs::write(dir.join(file), b"").unwrap();
+    }
+    dir
+}
+
+#[test]
+fn test_expand_input_file() {
+    let dir = create_temp_dir("file");
+    let file = dir.join("notes.txt");
+
+    let files = expand_input(&file, false).unwrap();
+    assert_eq!(files, vec![file]);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_expand_input_directory() {
+    let dir = create_temp_dir("dir");
+
+    let files = expand_input(&dir, false).unwrap();
+    assert_eq!(files, vec![dir.join("a.MP3"), dir.join("b.mp3")]);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_expand_input_directory_recursive() {
+    let dir = create_temp_dir("recursive");
+
+    let files = expand_input(&dir, true).unwrap();
+    assert_eq!(
+        files,
+        vec![dir.join("a.MP3"), dir.join("b.mp3"), dir.join("sub/c.mp3")]
+    );
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_expand_input_glob() {
+    let dir = create_temp_dir("glob");
+
+    let files = expand_input(&dir.join("*.mp3"), false).unwrap();
+    assert_eq!(files, vec![dir.join("b.mp3")]);
+
+    let files = expand_input(&dir.join("**/*.mp3"), false).unwrap();
+    assert_eq!(files, vec![dir.join("b.mp3"), dir.join("sub/c.mp3")]);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_expand_input_errors() {
+    let dir = create_temp_dir("errors");
+
+    let result = expand_input(&dir.join("missing.mp3"), false);
+    assert!(result.unwrap_err().contains("no existe"));
+
+    let result = expand_input(&dir.join("*.flac"), false);
+    assert!(result.unwrap_err().contains("Ningún archivo"));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_diff_tags_no_changes() {
+    let mut tag = Tag::new();
+    tag.set_title("Title");
+
+    assert!(diff_tags(&tag, &tag.clone()).is_empty());
+}
+
+#[test]
+fn test_diff_tags_added_changed_removed() {
+    let mut before = Tag::new();
+    before.set_title("Old Title");
+    before.set_album("Album");
+
+    let mut after = before.clone();
+    after.set_title("New Title");
+    after.remove_album();
+    after.set_artist("Artist");
+
+    let changes = diff_tags(&before, &after);
+    assert_eq!(
+        changes,
+        vec![
+            FrameChange::Changed {
+                id: "TIT2".to_string(),
+                old: "Old Title".to_string(),
+                new: "New Title".to_string(),
+            },
+            FrameChange::Added {
+                id: "TPE1".to_string(),
+                value: "Artist".to_string(),
+            },
+            FrameChange::Removed {
+                id: "TALB".to_string(),
+                value: "Album".to_string(),
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_diff_tags_pictures_and_lyrics() {
+    let before = Tag::new();
+    let mut after = Tag::new();
+    add_cover_art(&mut after, Path::new("cover.jpg"), vec![0xFF, 0xD8]).unwrap();
+    add_lyrics(&mut after, "Line 1\nLine 2", "spa", "");
+
+    let changes = diff_tags(&before, &after);
+    assert_eq!(changes.len(), 2);
+    assert_eq!(
+        changes[0],
+        FrameChange::Added {
+            id: "APIC".to_string(),
+            value: "CoverFront, image/jpeg, 2 bytes".to_string(),
+        }
+    );
+    assert_eq!(
+        changes[1],
+        FrameChange::Added {
+            id: "USLT".to_string(),
+            value: "[spa] Line 1… (2 líneas)".to_string(),
+        }
+    );
+}
+
+#[test]
+fn test_diff_tags_synced_lyrics_key() {
+    let lines = [(0, "Intro".to_string())];
+    let mut before = Tag::new();
+    add_synced_lyrics(&mut before, &lines, "spa", "Karaoke");
+
+    // add_frame sustituye el SYLT del mismo idioma aunque cambie la
+    // descripción, así que el diff lo muestra como un cambio
+    let mut after = before.clone();
+    add_synced_lyrics(&mut after, &lines, "spa", "Otra");
+    assert_eq!(after.synchronised_lyrics().count(), 1);
+
+    let changes = diff_tags(&before, &after);
+    assert_eq!(changes.len(), 1);
+    assert!(matches!(&changes[0], FrameChange::Changed { id, .. } if id == "SYLT"));
+}
+
+#[test]
+fn test_display_diff() {
+    let changes = vec![FrameChange::Removed {
+        id: "TIT2".to_string(),
+        value: "Title".to_string(),
+    }];
+    // Este test solo verifica que display_diff no hace panic
+    display_diff(&changes);
+    display_diff(&[]);
+}
+
+/// Crea un tag con todos los frames que cubre el sidecar
+fn create_full_tag() -> Tag {
+    let mut tag = Tag::new();
+    let artists = vec!["Artist 1".to_string(), "Artist 2".to_string()];
+    apply_metadata(
+        &mut tag,
+        Some("Title"),
+        &artists,
+        Some("Album"),
+        Some(2026),
+        Some("Podcast"),
+        Some(5),
+        Some(2),
+        Some("2026-01-22"),
+        Some("© 2026"),
+        Some("Composer"),
+        Some("Subtitle"),
+        Some("Original"),
+        Some("Album Artist"),
+    );
+    add_lyrics(&mut tag, "Línea 1\nLínea 2", "spa", "");
+    tag.add_frame(Frame::with_content(
+        "USLT",
+        Content::Lyrics(id3::frame::Lyrics {
+            lang: "eng".to_string(),
+            description: "Translation".to_string(),
+            text: "Line 1\nLine 2".to_string(),
+        }),
+    ));
+    add_url(&mut tag, "https://example.com");
+    add_url(&mut tag, "https://example.org");
+    add_apple_metadata(
+        &mut tag,
+        true,
+        Some("Album Sort"),
+        Some("Artist Sort"),
+        Some("Title Sort"),
+    );
+    add_cover_art(
+        &mut tag,
+        Path::new("cover.png"),
+        vec![0x89, 0x50, 0x4E, 0x47],
+    )
+    .unwrap();
+    apply_totals(&mut tag, None, Some(12), None, Some(3)).unwrap();
+    add_comment(&mut tag, "Comentario", "spa", "");
+    add_comment(&mut tag, "Notes", "eng", "Notes");
+    add_synced_lyrics(
+        &mut tag,
+        &[(0, "Intro".to_string()), (12_500, "Estrofa".to_string())],
+        "spa",
+        "",
+    );
+    add_user_text(&mut tag, "MOOD", "Calm");
+    add_user_url(&mut tag, "Donate", "https://example.com/donate");
+    add_podcast_metadata(
+        &mut tag,
+        &PodcastMetadata {
+            podcast: true,
+            category: Some("Technology"),
+            description: Some("Descripción larga"),
+            keywords: Some("linux, rust"),
+            episode_id: Some("ep-42"),
+            feed_url: Some("https://example.com/feed.xml"),
+        },
+    );
+    add_chapter(
+        &mut tag,
+        &NewChapter {
+            start: 0,
+            end: None,
+            title: "Intro".to_string(),
+            url: Some("https://example.com/intro".to_string()),
+            image: None,
+        },
+    )
+    .unwrap();
+    tag.add_frame(id3::frame::Chapter {
+        element_id: "image".to_string(),
+        start_time: 60_000,
+        end_time: 120_000,
+        start_offset: u32::MAX,
+        end_offset: u32::MAX,
+        frames: vec![
+            Frame::text("TIT2", "Tema 1"),
+            Frame::with_content(
+                "APIC",
+                Content::Picture(Picture {
+                    mime_type: "image/png".to_string(),
+                    picture_type: PictureType::Other,
+                    description: "Tema 1".to_string(),
+                    data: vec![0x89, 0x50, 0x4E, 0x47, 0x0D],
+                }),
+            ),
+        ],
+    });
+    rebuild_chapters(&mut tag);
+    set_raw_frame(&mut tag, "TKEY", "Am").unwrap();
+    set_raw_frame(&mut tag, "WPAY", "https://example.com/pay").unwrap();
+    tag
+}
+
+#[test]
+fn test_parse_picture_type() {
+    assert_eq!(
+        parse_picture_type("CoverFront"),
+        Some(PictureType::CoverFront)
+    );
+    assert_eq!(
+        parse_picture_type("cover_back"),
+        Some(PictureType::CoverBack)
+    );
+    assert_eq!(parse_picture_type("band-logo"), Some(PictureType::BandLogo));
+    assert_eq!(parse_picture_type("3"), Some(PictureType::CoverFront));
+    assert_eq!(parse_picture_type("42"), Some(PictureType::Undefined(42)));
+    assert_eq!(parse_picture_type("portada"), None);
+
+    assert_eq!(picture_type_name(PictureType::CoverFront), "CoverFront");
+    assert_eq!(picture_type_name(PictureType::Undefined(42)), "42");
+}
+
+#[test]
+fn test_mime_extension() {
+    assert_eq!(mime_extension("image/jpeg"), "jpg");
+    assert_eq!(mime_extension("IMAGE/PNG"), "png");
+    assert_eq!(mime_extension("image/webp"), "webp");
+    assert_eq!(mime_extension("application/octet-stream"), "bin");
+}
+
+#[test]
+fn test_export_sidecar() {
+    let sidecar = export_sidecar(&create_full_tag());
+
+    assert_eq!(sidecar.title.as_deref(), Some("Title"));
+    assert_eq!(sidecar.artist.as_deref(), Some("Artist 1; Artist 2"));
+    assert_eq!(sidecar.season, Some(2));
+    assert_eq!(sidecar.date.as_deref(), Some("2026-01-22"));
+    assert_eq!(sidecar.lyrics.len(), 2);
+    assert_eq!(
+        sidecar.urls,
+        vec!["https://example.com", "https://example.org"]
+    );
+    assert!(sidecar.compilation);
+    assert_eq!(sidecar.pictures.len(), 1);
+    assert_eq!(sidecar.pictures[0].picture_type, "CoverFront");
+    assert_eq!(sidecar.pictures[0].data.as_deref(), Some("iVBORw=="));
+    assert_eq!(sidecar.track_total, Some(12));
+    assert_eq!(sidecar.season_total, Some(3));
+    assert_eq!(sidecar.comments.len(), 2);
+    assert_eq!(sidecar.synced_lyrics[0].lines[1].time, 12_500);
+    assert_eq!(sidecar.txxx.get("MOOD").map(String::as_str), Some("Calm"));
+    assert_eq!(
+        sidecar.wxxx.get("Donate").map(String::as_str),
+        Some("https://example.com/donate")
+    );
+    assert!(sidecar.podcast);
+    assert_eq!(sidecar.episode_id.as_deref(), Some("ep-42"));
+    // Los frames sin campo propio van por su ID; los que tienen campo no se repiten
+    assert_eq!(
+        sidecar.frames.keys().collect::<Vec<_>>(),
+        vec!["TKEY", "WPAY"]
+    );
+    assert_eq!(sidecar.chapters.len(), 2);
+    assert_eq!(sidecar.chapters[0].end, "00:01:00.000");
+    assert_eq!(
+        sidecar.chapters[0].url.as_deref(),
+        Some("https://example.com/intro")
+    );
+    assert!(sidecar.chapters[1].image.is_some());
+}
+
+#[test]
+fn test_sidecar_round_trip() {
+    let original = create_full_tag();
+
+    let text = export_sidecar(&original).to_json().unwrap();
+    let sidecar = TagSidecar::from_json(&text).unwrap();
+
+    let mut tag = Tag::new();
+    let changed = import_sidecar(&mut tag, &sidecar, Path::new(".")).unwrap();
+
+    assert!(changed);
+    assert!(diff_tags(&original, &tag).is_empty());
+    assert_eq!(tag.get("PCST"), original.get("PCST"));
+    assert_eq!(tag.get("CTOC"), original.get("CTOC"));
+}
+
+#[test]
+fn test_import_sidecar_partial_preserves_existing() {
+    let mut tag = Tag::new();
+    tag.set_title("Original");
+    tag.set_album("Album");
+
+    let sidecar = TagSidecar::from_json(r#"{"title": "New"}"#).unwrap();
+    import_sidecar(&mut tag, &sidecar, Path::new(".")).unwrap();
+
+    assert_eq!(tag.title(), Some("New"));
+    assert_eq!(tag.album(), Some("Album"));
+}
+
+#[test]
+fn test_import_sidecar_errors() {
+    let mut tag = Tag::new();
+
+    let sidecar = TagSidecar::from_json(r#"{"date": "mañana"}"#).unwrap();
+    assert!(import_sidecar(&mut tag, &sidecar, Path::new(".")).is_err());
+
+    let sidecar =
+        TagSidecar::from_json(r#"{"pictures": [{"picture_type": "portada", "data": "AA=="}]}"#)
+            .unwrap();
+    assert!(import_sidecar(&mut tag, &sidecar, Path::new(".")).is_err());
+
+    let sidecar =
+        TagSidecar::from_json(r#"{"pictures": [{"picture_type": "CoverFront"}]}"#).unwrap();
+    assert!(import_sidecar(&mut tag, &sidecar, Path::new(".")).is_err());
+
+    let sidecar =
+        TagSidecar::from_json(r#"{"chapters": [{"start": "1:75", "end": "2:00"}]}"#).unwrap();
+    assert!(import_sidecar(&mut tag, &sidecar, Path::new(".")).is_err());
+
+    // Un total sin número de pista no se puede aplicar
+    let sidecar = TagSidecar::from_json(r#"{"track_total": 12}"#).unwrap();
+    assert!(import_sidecar(&mut tag, &sidecar, Path::new(".")).is_err());
+
+    // Frames que --frame no admite
+    let sidecar = TagSidecar::from_json(r#"{"frames": {"APIC": "x"}}"#).unwrap();
+    assert!(import_sidecar(&mut tag, &sidecar, Path::new(".")).is_err());
+
+    // Una carátula en base64 sin mime_type ni formato reconocible
+    let sidecar =
+        TagSidecar::from_json(r#"{"pictures": [{"picture_type": "CoverFront", "data": "AAAA"}]}"#)
+            .unwrap();
+    assert!(import_sidecar(&mut tag, &sidecar, Path::new(".")).is_err());
+
+    // Los campos desconocidos se rechazan para detectar erratas
+    assert!(TagSidecar::from_json(r#"{"tittle": "Typo"}"#).is_err());
+}
+
+#[test]
+fn test_import_sidecar_sniffs_picture_mime_type() {
+    // PNG en base64 sin mime_type: no debe etiquetarse como JPEG
+    let sidecar = TagSidecar::from_json(
+        r#"{"pictures": [{"picture_type": "CoverFront", "data": "iVBORw0KGgo="}]}"#,
+    )
+    .unwrap();
+
+    let mut tag = Tag::new();
+    import_sidecar(&mut tag, &sidecar, Path::new(".")).unwrap();
+    assert_eq!(tag.pictures().next().unwrap().mime_type, "image/png");
+}
+
+#[test]
+fn test_sidecar_round_trip_generic_frames() {
+    let mut original = Tag::new();
+    set_raw_frame(&mut original, "TKEY", "Am").unwrap();
+    set_raw_frame(&mut original, "TBPM", "128").unwrap();
+    set_raw_frame(&mut original, "WPAY", "https://example.com/pay").unwrap();
+
+    let text = export_sidecar(&original).to_json().unwrap();
+    let sidecar = TagSidecar::from_json(&text).unwrap();
+
+    let mut tag = Tag::new();
+    assert!(import_sidecar(&mut tag, &sidecar, Path::new(".")).unwrap());
+    assert!(diff_tags(&original, &tag).is_empty());
+    assert_eq!(frame_text(&tag, "TKEY").as_deref(), Some("Am"));
+}
+
+#[test]
+fn test_sidecar_external_pictures() {
+    let dir = create_temp_dir("sidecar");
+    let original = create_full_tag();
+
+    let mut sidecar = export_sidecar(&original);
+    externalize_pictures(&mut sidecar, &dir.join("covers"), "episode").unwrap();
+
+    let picture = &sidecar.pictures[0];
+    assert_eq!(picture.data, None);
+    assert_eq!(picture.file, Some(dir.join("covers/episode-1.png")));
+    assert_eq!(
+        std::fs::read(dir.join("covers/episode-1.png")).unwrap(),
+        vec![0x89, 0x50, 0x4E, 0x47]
+    );
+    let image = sidecar.chapters[1].image.as_ref().unwrap();
+    assert_eq!(image.file, Some(dir.join("covers/episode-chp1.png")));
+
+    let mut tag = Tag::new();
+    import_sidecar(&mut tag, &sidecar, &dir).unwrap();
+    assert!(diff_tags(&original, &tag).is_empty());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_sanitize_filename() {
+    assert_eq!(sanitize_filename("AC/DC: Live?"), "AC_DC_ Live_");
+    assert_eq!(sanitize_filename("  Título normal  "), "Título normal");
+    assert_eq!(sanitize_filename(".."), "_");
+    assert_eq!(sanitize_filename("a\\b|c*d\"e<f>g"), "a_b_c_d_e_f_g");
+    assert_eq!(sanitize_filename("line\nbreak"), "line_break");
+}
+
+#[test]
+fn test_render_template() {
+    let mut tag = Tag::new();
+    tag.set_title("Bohemian Rhapsody");
+    tag.set_album("A Night at the Opera");
+    tag.set_album_artist("Queen");
+    tag.set_track(11);
+    tag.set_disc(1);
+    tag.set_year(1975);
+
+    let path = render_template("{album_artist}/{album}/{track:02} - {title}.mp3", &tag).unwrap();
+    assert_eq!(
+        path,
+        "Queen/A Night at the Opera/11 - Bohemian Rhapsody.mp3"
+    );
+
+    let path = render_template("S{season:02}E{track:03} ({year}) {{x}}.mp3", &tag).unwrap();
+    assert_eq!(path, "S01E011 (1975) {x}.mp3");
+}
+
+#[test]
+fn test_render_template_sanitizes_values() {
+    let mut tag = Tag::new();
+    tag.set_artist("AC/DC");
+    tag.set_title("What?");
+
+    let path = render_template("{artist}/{title}.mp3", &tag).unwrap();
+    assert_eq!(path, "AC_DC/What_.mp3");
+}
+
+#[test]
+fn test_render_template_year_from_date() {
+    let mut tag = Tag::new();
+    tag.set_date_recorded("2026-01-22".parse().unwrap());
+
+    assert_eq!(render_template("{year}", &tag).unwrap(), "2026");
+}
+
+#[test]
+fn test_render_template_errors() {
+    let mut tag = Tag::new();
+    tag.set_title("Title");
+
+    assert!(
+        render_template("{album}.mp3", &tag)
+            .unwrap_err()
+            .contains("album")
+    );
+    assert!(
+        render_template("{unknown}.mp3", &tag)
+            .unwrap_err()
+            .contains("desconocido")
+    );
+    assert!(render_template("{title:02}.mp3", &tag).is_err());
+    assert!(render_template("{title.mp3", &tag).is_err());
+    assert!(render_template("title}.mp3", &tag).is_err());
+}
+
+#[test]
+fn test_find_rename_collisions() {
+    let dir = create_temp_dir("collisions");
+    let moves = vec![
+        (dir.join("b.mp3"), dir.join("new.mp3")),
+        (dir.join("a.MP3"), dir.join("new.mp3")),
+        (dir.join("sub/c.mp3"), dir.join("b.mp3")),
+        (dir.join("a.MP3"), dir.join("a.MP3")),
+    ];
+
+    let collisions = find_rename_collisions(&moves);
+    let indexes: Vec<usize> = collisions.iter().map(|(i, _)| *i).collect();
+    assert_eq!(indexes, vec![1, 2]);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_move_file_creates_directories() {
+    let dir = create_temp_dir("move");
+    let to = dir.join("Artist/Album/01 - Title.mp3");
+
+    move_file(&dir.join("b.mp3"), &to).unwrap();
+    assert!(to.exists());
+    assert!(!dir.join("b.mp3").exists());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_parse_filename_with_directories() {
+    let path = Path::new("/music/Queen - A Night at the Opera/11 - Bohemian Rhapsody.mp3");
+    let fields = parse_filename(path, "%artist% - %album%/%track% - %title%").unwrap();
+
+    assert_eq!(
+        fields,
+        vec![
+            ("artist".to_string(), "Queen".to_string()),
+            ("album".to_string(), "A Night at the Opera".to_string()),
+            ("track".to_string(), "11".to_string()),
+            ("title".to_string(), "Bohemian Rhapsody".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_parse_filename_lazy_fields_and_ignore() {
+    let path = Path::new("03 - Intro - Parte 2 [web].mp3");
+    let fields = parse_filename(path, "%track% - %title% [%ignore%]").unwrap();
+
+    assert_eq!(
+        fields,
+        vec![
+            ("track".to_string(), "03".to_string()),
+            ("title".to_string(), "Intro - Parte 2".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_parse_filename_errors() {
+    let path = Path::new("song.mp3");
+
+    assert!(parse_filename(path, "%track% - %title%").is_err());
+    assert!(
+        parse_filename(path, "%unknown%")
+            .unwrap_err()
+            .contains("desconocido")
+    );
+    assert!(parse_filename(path, "%title").is_err());
+    assert!(
+        parse_filename(path, "%album%/%title%")
+            .unwrap_err()
+            .contains("niveles")
+    );
+}
+
+#[test]
+fn test_apply_filename_fields() {
+    let mut tag = Tag::new();
+    let fields = vec![
+        ("artist".to_string(), "Artist".to_string()),
+        ("track".to_string(), "07".to_string()),
+        ("season".to_string(), "2".to_string()),
+        ("year".to_string(), "2026".to_string()),
+        ("title".to_string(), "Title".to_string()),
+    ];
+
+    let changed = apply_filename_fields(&mut tag, &fields).unwrap();
+    assert!(changed);
+    assert_eq!(tag.artist(), Some("Artist"));
+    assert_eq!(tag.track(), Some(7));
+    assert_eq!(tag.disc(), Some(2));
+    assert_eq!(tag.year(), Some(2026));
+    assert_eq!(tag.title(), Some("Title"));
+}
+
+#[test]
+fn test_apply_filename_fields_invalid_number() {
+    let mut tag = Tag::new();
+    let fields = vec![("track".to_string(), "uno".to_string())];
+
+    assert!(apply_filename_fields(&mut tag, &fields).is_err());
+    assert_eq!(tag.track(), None);
+}
+
+#[test]
+fn test_convert_tag_to_v23() {
+    let mut tag = Tag::new();
+    tag.set_date_recorded("2026-01-22T10:30:15".parse().unwrap());
+    tag.set_original_date_released("1999".parse().unwrap());
+    tag.set_text("TMOO", "Calm");
+    tag.add_frame(Frame::with_content(
+        "TIPL",
+        Content::InvolvedPeopleList(id3::frame::InvolvedPeopleList {
+            items: vec![id3::frame::InvolvedPeopleListItem {
+                involvement: "producer".to_string(),
+                involvee: "Someone".to_string(),
+            }],
+        }),
+    ));
+
+    let report = convert_tag(&mut tag, id3::Version::Id3v23);
+
+    assert_eq!(tag.get("TDRC"), None);
+    assert_eq!(tag.get("TDOR"), None);
+    assert_eq!(tag.get("TIPL"), None);
+    assert_eq!(tag.get("TMOO"), None);
+    assert_eq!(tag.year(), Some(2026));
+    assert_eq!(tag.get("TDAT").unwrap().content().text(), Some("2201"));
+    assert_eq!(tag.get("TIME").unwrap().content().text(), Some("1030"));
+    assert_eq!(tag.get("TORY").unwrap().content().text(), Some("1999"));
+    assert!(tag.get("IPLS").is_some());
+    assert!(report.lossy.iter().any(|l| l.contains("segundos")));
+    assert!(report.lossy.iter().any(|l| l.starts_with("TMOO")));
+}
+
+#[test]
+fn test_convert_tag_to_v24() {
+    let mut tag = Tag::new();
+    tag.set_year(2026);
+    tag.set_text("TDAT", "2201");
+    tag.set_text("TIME", "1030");
+    tag.set_text("TORY", "1999");
+    tag.set_text("TSIZ", "12345");
+
+    let report = convert_tag(&mut tag, id3::Version::Id3v24);
+
+    assert_eq!(tag.year(), Some(2026));
+    assert_eq!(tag.get("TDAT"), None);
+    assert_eq!(tag.get("TIME"), None);
+    assert_eq!(tag.get("TORY"), None);
+    assert_eq!(tag.get("TSIZ"), None);
+    assert_eq!(
+        tag.date_recorded().map(|ts| ts.to_string()),
+        Some("2026-01-22T10:30".to_string())
+    );
+    assert_eq!(tag.original_date_released().map(|ts| ts.year), Some(1999));
+    assert!(report.changes.iter().any(|c| c.contains("TDRC")));
+    assert!(report.lossy.iter().any(|l| l.starts_with("TSIZ")));
+    assert!(lint_tag(&tag).iter().all(|i| i.code != "obsolete-frame"));
+}
+
+#[test]
+fn test_convert_tag_to_v24_keeps_existing_date() {
+    let mut tag = Tag::new();
+    tag.set_year(2026);
+    tag.set_date_recorded("2025-12-31".parse().unwrap());
+
+    let report = convert_tag(&mut tag, id3::Version::Id3v24);
+
+    assert!(report.changes.is_empty());
+    assert!(report.lossy.is_empty());
+    assert_eq!(tag.year(), Some(2026));
+    assert_eq!(
+        tag.date_recorded().map(|ts| ts.to_string()),
+        Some("2025-12-31".to_string())
+    );
+}
+
+#[test]
+fn test_convert_tag_roundtrip_is_stable() {
+    let mut tag = Tag::new();
+    tag.set_title("Title");
+    tag.set_date_recorded("2026-01-22".parse().unwrap());
+
+    convert_tag(&mut tag, id3::Version::Id3v23);
+    let report = convert_tag(&mut tag, id3::Version::Id3v24);
+
+    assert!(report.lossy.is_empty());
+    assert_eq!(tag.title(), Some("Title"));
+    assert_eq!(
+        tag.date_recorded().map(|ts| ts.to_string()),
+        Some("2026-01-22".to_string())
+    );
+}
+
+#[test]
+fn test_validate_frame_id() {
+    assert_eq!(validate_frame_id("tkey"), Ok("TKEY".to_string()));
+    assert_eq!(validate_frame_id("WOAR"), Ok("WOAR".to_string()));
+    assert!(validate_frame_id("TKE").is_err());
+    assert!(validate_frame_id("T-EY").is_err());
+    // TYER es de ID3v2.3
+    assert!(
+        validate_frame_id("TYER")
+            .unwrap_err()
+            .contains("no existe en ID3v2.4")
+    );
+}
+
+#[test]
+fn test_parse_frame_assignment() {
+    assert_eq!(
+        parse_frame_assignment("TKEY=Am"),
+        Ok(("TKEY".to_string(), "Am".to_string()))
+    );
+    assert_eq!(
+        parse_frame_assignment("wpay=https://example.com/?a=b"),
+        Ok(("WPAY".to_string(), "https://example.com/?a=b".to_string()))
+    );
+    assert!(parse_frame_assignment("TKEY").is_err());
+    assert!(parse_frame_assignment("TKEY=").is_err());
+    assert!(parse_frame_assignment("APIC=cover.jpg").is_err());
+    assert!(parse_frame_assignment("TXXX=value").is_err());
+}
+
+#[test]
+fn test_set_raw_frame() {
+    let mut tag = Tag::new();
+
+    set_raw_frame(&mut tag, "TBPM", "120").unwrap();
+    set_raw_frame(&mut tag, "TBPM", "128").unwrap();
+    set_raw_frame(&mut tag, "WPAY", "https://example.com/pay").unwrap();
+
+    assert_eq!(tag.frames().filter(|f| f.id() == "TBPM").count(), 1);
+    assert_eq!(tag.get("TBPM").unwrap().content().text(), Some("128"));
+    assert_eq!(
+        tag.get("WPAY").unwrap().content().link(),
+        Some("https://example.com/pay")
+    );
+    assert!(set_raw_frame(&mut tag, "COMM", "hola").is_err());
+}
+
+#[test]
+fn test_set_raw_frame_rejects_involved_people() {
+    let mut tag = Tag::new();
+
+    for id in ["TIPL", "tmcl", "IPLS"] {
+        let error = set_raw_frame(&mut tag, id, "producer:Ana").unwrap_err();
+        assert!(error.contains("lista de personas"), "{}", error);
+    }
+    assert!(parse_frame_assignment("TIPL=mix:Luis").is_err());
+    assert_eq!(tag.frames().count(), 0);
+}
+
+#[test]
+fn test_set_raw_frame_validates_timestamps() {
+    let mut tag = Tag::new();
+
+    set_raw_frame(&mut tag, "TDRC", "2024-05-17").unwrap();
+    set_raw_frame(&mut tag, "TDOR", "1999").unwrap();
+    set_raw_frame(&mut tag, "TDTG", "2024-05-17T10:30:00").unwrap();
+    assert_eq!(
+        tag.get("TDRC").unwrap().content().text(),
+        Some("2024-05-17")
+    );
+
+    for id in ["TDRC", "TDOR", "TDRL", "TDEN", "TDTG"] {
+        let error = set_raw_frame(&mut tag, id, "mañana").unwrap_err();
+        assert!(error.contains("no es una fecha válida"), "{}", error);
+    }
+    assert!(parse_frame_assignment("TDRL=17/05/2024").is_err());
+    assert!(parse_frame_assignment("TDRL=2024-13-01").is_err());
+    assert_eq!(
+        tag.get("TDRC").unwrap().content().text(),
+        Some("2024-05-17")
+    );
+}
+
+#[test]
+fn test_remove_tags_by_frame_id() {
+    let mut tag = Tag::new();
+    tag.set_text("TKEY", "Am");
+    tag.set_text("TBPM", "128");
+    tag.set_title("Title");
+
+    let removed = remove_tags(&mut tag, &["tkey".to_string(), "ZZZZ".to_string()]);
+
+    assert!(removed);
+    assert!(tag.get("TKEY").is_none());
+    assert!(tag.get("TBPM").is_some());
+    assert_eq!(tag.title(), Some("Title"));
+}
+
+#[test]
+fn test_parse_description_assignment() {
+    assert_eq!(
+        parse_description_assignment("MusicBrainz Album Id=1234"),
+        Ok(("MusicBrainz Album Id".to_string(), "1234".to_string()))
+    );
+    assert_eq!(
+        parse_description_assignment("Donaciones=https://example.com/?a=b"),
+        Ok((
+            "Donaciones".to_string(),
+            "https://example.com/?a=b".to_string()
+        ))
+    );
+    assert!(parse_description_assignment("sin-igual").is_err());
+    assert!(parse_description_assignment("=valor").is_err());
+    assert!(parse_description_assignment("CATALOGO=").is_err());
+}
+
+#[test]
+fn test_add_user_frames_replace_by_description() {
+    let mut tag = Tag::new();
+
+    add_user_text(&mut tag, "CATALOGO", "AT-001");
+    add_user_text(&mut tag, "REPLAYGAIN_TRACK_GAIN", "-6.5 dB");
+    add_user_text(&mut tag, "CATALOGO", "AT-002");
+    add_user_url(&mut tag, "Donaciones", "https://example.com/pay");
+
+    let texts: Vec<_> = tag.extended_texts().collect();
+    assert_eq!(texts.len(), 2);
+    assert!(
+        texts
+            .iter()
+            .any(|t| t.description == "CATALOGO" && t.value == "AT-002")
+    );
+    assert_eq!(tag.extended_links().count(), 1);
+}
+
+#[test]
+fn test_remove_user_frame_by_description() {
+    let mut tag = Tag::new();
+    add_user_text(&mut tag, "CATALOGO", "AT-001");
+    add_user_text(&mut tag, "MusicBrainz Album Id", "1234");
+    add_user_url(&mut tag, "Donaciones", "https://example.com/pay");
+
+    let removed = remove_tags(
+        &mut tag,
+        &["txxx:CATALOGO".to_string(), "WXXX:Donaciones".to_string()],
+    );
+
+    assert!(removed);
+    let texts: Vec<_> = tag.extended_texts().collect();
+    assert_eq!(texts.len(), 1);
+    assert_eq!(texts[0].description, "MusicBrainz Album Id");
+    assert_eq!(tag.extended_links().count(), 0);
+    assert!(!remove_user_frame(&mut tag, "TXXX", "CATALOGO"));
+}
+
+#[test]
+fn test_validate_language() {
+    assert_eq!(validate_language("spa"), Ok("spa".to_string()));
+    assert_eq!(validate_language("ENG"), Ok("eng".to_string()));
+    assert!(validate_language("es").is_err());
+    assert!(validate_language("es1").is_err());
+    assert!(validate_language("ñam").is_err());
+}
+
+#[test]
+fn test_add_comment_keyed_by_lang_and_description() {
+    let mut tag = Tag::new();
+
+    add_comment(&mut tag, "Primero", "spa", "");
+    add_comment(&mut tag, "First", "eng", "");
+    add_comment(&mut tag, "Notas", "spa", "notas");
+    add_comment(&mut tag, "Segundo", "spa", "");
+
+    let comments: Vec<_> = tag.comments().collect();
+    assert_eq!(comments.len(), 3);
+    assert!(
+        comments
+            .iter()
+            .any(|c| c.lang == "spa" && c.description.is_empty() && c.text == "Segundo")
+    );
+    assert!(
+        comments
+            .iter()
+            .any(|c| c.lang == "eng" && c.text == "First")
+    );
+}
+
+#[test]
+fn test_remove_comment() {
+    let mut tag = Tag::new();
+    add_comment(&mut tag, "Primero", "spa", "");
+    add_comment(&mut tag, "First", "eng", "");
+
+    assert!(remove_tags(&mut tag, &["comentario".to_string()]));
+    assert_eq!(tag.comments().count(), 0);
+}
+
+#[test]
+fn test_add_lyrics_multiple_languages() {
+    let mut tag = Tag::new();
+
+    add_lyrics(&mut tag, "Hola", "spa", "");
+    add_lyrics(&mut tag, "Hello", "eng", "");
+    add_lyrics(&mut tag, "Hello (radio edit)", "eng", "radio");
+    add_lyrics(&mut tag, "Hola de nuevo", "spa", "");
+
+    let lyrics: Vec<_> = tag.lyrics().collect();
+    assert_eq!(lyrics.len(), 3);
+    assert!(
+        lyrics
+            .iter()
+            .any(|l| l.lang == "spa" && l.text == "Hola de nuevo")
+    );
+    assert!(lyrics.iter().any(|l| l.lang == "eng" && l.text == "Hello"));
+    assert!(
+        lyrics
+            .iter()
+            .any(|l| l.lang == "eng" && l.description == "radio")
+    );
+}
+
+#[test]
+fn test_decode_text_utf8() {
+    assert_eq!(
+        decode_text(b"Hola\nmundo\n\n"),
+        Ok("Hola\nmundo\n\n".to_string())
+    );
+    assert_eq!(
+        decode_text(&[0xEF, 0xBB, 0xBF, b'L', b'1', b'\r', b'\n', b'L', b'2']),
+        Ok("L1\nL2".to_string())
+    );
+    assert!(decode_text(&[0xC3, 0x28]).is_err());
+}
+
+#[test]
+fn test_decode_text_utf16() {
+    let mut le = vec![0xFF, 0xFE];
+    le.extend("Canción\r\n".encode_utf16().flat_map(|u| u.to_le_bytes()));
+    assert_eq!(decode_text(&le), Ok("Canción\n".to_string()));
+
+    let mut be = vec![0xFE, 0xFF];
+    be.extend("Canción".encode_utf16().flat_map(|u| u.to_be_bytes()));
+    assert_eq!(decode_text(&be), Ok("Canción".to_string()));
+
+    // Número impar de bytes
+    assert!(decode_text(&[0xFF, 0xFE, 0x41]).is_err());
+}
+
+#[test]
+fn test_decode_text_utf16_without_bom() {
+    let le: Vec<u8> = "Canción\r\nSegunda línea"
+        .encode_utf16()
+        .flat_map(|u| u.to_le_bytes())
+        .collect();
+    assert_eq!(decode_text(&le), Ok("Canción\nSegunda línea".to_string()));
+
+    let be: Vec<u8> = "Ñandú y Āmbar"
+        .encode_utf16()
+        .flat_map(|u| u.to_be_bytes())
+        .collect();
+    assert_eq!(decode_text(&be), Ok("Ñandú y Āmbar".to_string()));
+
+    // UTF-8 sin bytes nulos no se confunde con UTF-16
+    assert_eq!(decode_text("ab".as_bytes()), Ok("ab".to_string()));
+}
+
+#[test]
+fn test_read_text_source_missing_file() {
+    let error = read_text_source(Path::new("/nonexistent/letra.txt")).unwrap_err();
+    assert!(error.to_string().contains("No se pudo leer"));
+}
+
+#[test]
+fn test_parse_lrc() {
+    let lrc = "[ar:Artista]\n[ti:Título]\n[00:12.00][01:30.50]Estribillo\n[00:01.5]Intro\nSin marca\n[00:20]Verso\n[00:25.123]\n";
+
+    let lines = parse_lrc(lrc).unwrap();
+    assert_eq!(
+        lines,
+        vec![
+            (1500, "Intro".to_string()),
+            (12000, "Estribillo".to_string()),
+            (20000, "Verso".to_string()),
+            (25123, String::new()),
+            (90500, "Estribillo".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_parse_lrc_offset() {
+    let lines = parse_lrc("[offset:+500]\n[00:00.20]A\n[00:10.00]B\n").unwrap();
+    assert_eq!(lines, vec![(0, "A".to_string()), (9500, "B".to_string())]);
+
+    let lines = parse_lrc("[offset:-250]\n[00:10.00]B\n").unwrap();
+    assert_eq!(lines, vec![(10250, "B".to_string())]);
+}
+
+#[test]
+fn test_parse_lrc_errors() {
+    assert!(parse_lrc("[ar:Artista]\nSin marcas\n").is_err());
+    assert!(parse_lrc("[00:75.00]Segundos fuera de rango\n").is_err());
+    assert!(parse_lrc("[offset:abc]\n[00:01.00]A\n").is_err());
+}
+
+#[test]
+fn test_synced_lyrics_lrc_roundtrip() {
+    let mut tag = Tag::new();
+    let lines = parse_lrc("[00:01.50]Intro\n[01:02.03]Verso\n").unwrap();
+
+    add_synced_lyrics(&mut tag, &lines, "spa", "");
+    add_synced_lyrics(&mut tag, &lines, "spa", "");
+    add_synced_lyrics(&mut tag, &lines, "eng", "");
+    assert_eq!(tag.synchronised_lyrics().count(), 2);
+
+    let sylt = tag.synchronised_lyrics().next().unwrap();
+    assert_eq!(
+        format_lrc(sylt).unwrap(),
+        "[00:01.50]Intro\n[01:02.03]Verso\n"
+    );
+
+    assert!(remove_tags(&mut tag, &["synced_lyrics".to_string()]));
+    assert_eq!(tag.synchronised_lyrics().count(), 0);
+}
+
+#[test]
+fn test_parse_chapter_time() {
+    assert_eq!(parse_chapter_time("00:00:00"), Ok(0));
+    assert_eq!(parse_chapter_time("01:02:03"), Ok(3_723_000));
+    assert_eq!(parse_chapter_time("05:30.5"), Ok(330_500));
+    assert_eq!(parse_chapter_time("90"), Ok(90_000));
+    assert_eq!(parse_chapter_time("00:00:01.250"), Ok(1_250));
+    assert!(parse_chapter_time("00:61:00").is_err());
+    assert!(parse_chapter_time("1:2:3:4").is_err());
+    assert!(parse_chapter_time("abc").is_err());
+    assert_eq!(format_chapter_time(3_723_250), "01:02:03.250");
+}
+
+#[test]
+fn test_add_chapter_builds_toc() {
+    let mut tag = Tag::new();
+    for (start, title) in [(60_000, "Segundo"), (0, "Intro"), (120_000, "Final")] {
+        add_chapter(
+            &mut tag,
+            &NewChapter {
+                start,
+                title: title.to_string(),
+                ..NewChapter::default()
+            },
+        )
+        .unwrap();
+    }
+
+    let chapters = sorted_chapters(&tag);
+    assert_eq!(chapters.len(), 3);
+    assert_eq!(chapters[0].element_id, "chp0");
+    assert_eq!(chapters[0].end_time, 60_000);
+    assert_eq!(chapters[1].end_time, 120_000);
+    // Sin TLEN el último capítulo queda abierto
+    assert_eq!(chapters[2].end_time, 120_000);
+
+    let toc = tag.tables_of_contents().next().unwrap();
+    assert!(toc.top_level && toc.ordered);
+    assert_eq!(toc.elements, vec!["chp0", "chp1", "chp2"]);
+
+    tag.set_duration(180_000);
+    assert!(!rebuild_chapters(&mut tag));
+    assert_eq!(sorted_chapters(&tag)[2].end_time, 180_000);
+}
+
+#[test]
+fn test_rebuild_chapters_keeps_existing_toc() {
+    let mut tag = Tag::new();
+    for (id, start) in [("a", 0), ("b", 60_000)] {
+        tag.add_frame(id3::frame::Chapter {
+            element_id: id.to_string(),
+            start_time: start,
+            end_time: start + 60_000,
+            start_offset: u32::MAX,
+            end_offset: u32::MAX,
+            frames: vec![Frame::text("TIT2", id)],
+        });
+    }
+    tag.add_frame(id3::frame::TableOfContents {
+        element_id: "main".to_string(),
+        top_level: true,
+        ordered: true,
+        elements: vec!["a".to_string(), "part1".to_string()],
+        frames: vec![Frame::text("TIT2", "Episodio")],
+    });
+    tag.add_frame(id3::frame::TableOfContents {
+        element_id: "part1".to_string(),
+        top_level: false,
+        ordered: true,
+        elements: vec!["b".to_string()],
+        frames: Vec::new(),
+    });
+
+    add_chapter(
+        &mut tag,
+        &NewChapter {
+            start: 30_000,
+            title: "Nuevo".to_string(),
+            ..NewChapter::default()
+        },
+    )
+    .unwrap();
+
+    let toc = |tag: &Tag, id: &str| {
+        tag.tables_of_contents()
+            .find(|toc| toc.element_id == id)
+            .unwrap()
+            .clone()
+    };
+    // La tabla principal conserva sus subframes y la tabla anidada
+    let main = toc(&tag, "main");
+    assert_eq!(main.elements, vec!["chp0", "part1", "chp1"]);
+    assert_eq!(main.frames, vec![Frame::text("TIT2", "Episodio")]);
+    let part1 = toc(&tag, "part1");
+    assert!(!part1.top_level);
+    assert_eq!(part1.elements, vec!["chp2"]);
+    assert_eq!(tag.tables_of_contents().count(), 2);
+
+    // Los capítulos eliminados desaparecen de su tabla
+    remove_chapters(&mut tag, &["chp2".to_string()]).unwrap();
+    assert!(toc(&tag, "part1").elements.is_empty());
+    assert_eq!(toc(&tag, "main").elements, vec!["chp0", "part1", "chp1"]);
+}
+
+#[test]
+fn test_add_chapter_with_url_and_invalid_end() {
+    let mut tag = Tag::new();
+    let chapter = NewChapter {
+        start: 10_000,
+        end: Some(20_000),
+        title: "Noticia".to_string(),
+        url: Some("https://example.com".to_string()),
+        image: None,
+    };
+    add_chapter(&mut tag, &chapter).unwrap();
+
+    let stored = tag.chapters().next().unwrap();
+    assert_eq!(stored.end_time, 20_000);
+    assert!(stored.frames.iter().any(|f| f.id() == "WXXX"));
+
+    let invalid = NewChapter {
+        start: 30_000,
+        end: Some(30_000),
+        title: "Vacío".to_string(),
+        ..NewChapter::default()
+    };
+    assert!(add_chapter(&mut tag, &invalid).is_err());
+}
+
+#[test]
+fn test_remove_chapters() {
+    let mut tag = Tag::new();
+    let chapters = parse_chapter_list("00:00 A\n01:00 B\n02:00 C\n").unwrap();
+    for chapter in &chapters {
+        add_chapter(&mut tag, chapter).unwrap();
+    }
+
+    // Las posiciones se refieren a la lista original
+    let removed = remove_chapters(&mut tag, &["1".to_string(), "chp2".to_string()]).unwrap();
+    assert_eq!(removed.len(), 2);
+
+    let remaining = sorted_chapters(&tag);
+    assert_eq!(remaining.len(), 1);
+    assert_eq!(remaining[0].start_time, 60_000);
+    assert_eq!(remaining[0].element_id, "chp0");
+
+    assert!(remove_chapters(&mut tag, &["5".to_string()]).is_err());
+    assert_eq!(remove_all_chapters(&mut tag), 1);
+    assert_eq!(tag.tables_of_contents().count(), 0);
+}
+
+#[test]
+fn test_parse_chapter_list() {
+    let chapters =
+        parse_chapter_list("# Episodio 42\n\n00:00:00 Intro\n00:05:30.5  Tema principal\n")
+            .unwrap();
+    assert_eq!(chapters.len(), 2);
+    assert_eq!(chapters[1].start, 330_500);
+    assert_eq!(chapters[1].title, "Tema principal");
+
+    assert!(parse_chapter_list("00:00:00\n").is_err());
+    assert!(parse_chapter_list("xx:00 Intro\n").is_err());
+    assert!(parse_chapter_list("# vacío\n").is_err());
+}
+
+#[test]
+fn test_parse_chapter_json() {
+    let base = Path::new("/podcast");
+    let podlove = r#"[
+        {"start": "00:00:00.000", "title": "Intro", "href": "", "image": ""},
+        {"start": "00:01:30.500", "title": "Tema", "href": "https://example.com", "image": "tema.jpg"}
+    ]"#;
+    let chapters = parse_chapter_json(podlove, base).unwrap();
+    assert_eq!(chapters.len(), 2);
+    assert_eq!(chapters[0].url, None);
+    assert_eq!(chapters[1].start, 90_500);
+    assert_eq!(chapters[1].url.as_deref(), Some("https://example.com"));
+    assert_eq!(chapters[1].image, Some(base.join("tema.jpg")));
+
+    let podcasting = r#"{"version": "1.2.0", "chapters": [
+        {"startTime": 0, "title": "Intro", "img": "https://example.com/a.jpg"},
+        {"startTime": 65.5, "endTime": 70, "title": "Tema"}
+    ]}"#;
+    let chapters = parse_chapter_json(podcasting, base).unwrap();
+    assert_eq!(chapters[0].image, None);
+    assert_eq!(chapters[1].start, 65_500);
+    assert_eq!(chapters[1].end, Some(70_000));
+
+    assert!(parse_chapter_json("{\"foo\": 1}", base).is_err());
+}
+
+#[test]
+fn test_add_podcast_metadata() {
+    let mut tag = Tag::new();
+    let changed = add_podcast_metadata(
+        &mut tag,
+        &PodcastMetadata {
+            podcast: true,
+            category: Some("Technology"),
+            description: Some("Descripción\nlarga"),
+            keywords: Some("linux,rust"),
+            episode_id: Some("urn:uuid:1234"),
+            feed_url: Some("https://example.com/feed"),
+        },
+    );
+
+    assert!(changed);
+    assert!(tag.get("PCST").is_some());
+    assert_eq!(
+        tag.get("TCAT").unwrap().content().text(),
+        Some("Technology")
+    );
+    assert_eq!(
+        tag.get("TDES").unwrap().content().text(),
+        Some("Descripción\nlarga")
+    );
+    assert_eq!(
+        tag.get("TKWD").unwrap().content().text(),
+        Some("linux,rust")
+    );
+    assert_eq!(
+        tag.get("TGID").unwrap().content().text(),
+        Some("urn:uuid:1234")
+    );
+    assert_eq!(
+        tag.get("WFED").unwrap().content().link(),
+        Some("https://example.com/feed")
+    );
+
+    assert!(!add_podcast_metadata(&mut tag, &PodcastMetadata::default()));
+}
+
+#[test]
+fn test_add_podcast_flag_twice() {
+    let mut tag = Tag::new();
+    let metadata = PodcastMetadata {
+        podcast: true,
+        ..Default::default()
+    };
+
+    add_podcast_metadata(&mut tag, &metadata);
+    add_podcast_metadata(&mut tag, &metadata);
+
+    assert_eq!(tag.frames().filter(|f| f.id() == "PCST").count(), 1);
+    assert!(!lint_codes(&tag).contains(&"duplicate-frame"));
+}
+
+#[test]
+fn test_remove_podcast_metadata() {
+    let mut tag = Tag::new();
+    add_podcast_metadata(
+        &mut tag,
+        &PodcastMetadata {
+            podcast: true,
+            category: Some("Technology"),
+            feed_url: Some("https://example.com/feed"),
+            ..PodcastMetadata::default()
+        },
+    );
+
+    let removed = remove_tags(
+        &mut tag,
+        &[
+            "podcast".to_string(),
+            "categoría".to_string(),
+            "feed_url".to_string(),
+        ],
+    );
+
+    assert!(removed);
+    assert_eq!(tag.frames().count(), 0);
+}
+
+#[test]
+fn test_parse_number_with_total() {
+    assert_eq!(parse_number_with_total("3"), Ok((3, None)));
+    assert_eq!(parse_number_with_total("3/12"), Ok((3, Some(12))));
+    assert_eq!(parse_number_with_total(" 1 / 2 "), Ok((1, Some(2))));
+    assert!(parse_number_with_total("13/12").is_err());
+    assert!(parse_number_with_total("3/").is_err());
+    assert!(parse_number_with_total("tres").is_err());
+}
+
+#[test]
+fn test_apply_totals() {
+    let mut tag = Tag::new();
+    tag.set_track(3);
+    tag.set_disc(1);
+
+    assert_eq!(
+        apply_totals(&mut tag, None, Some(12), None, Some(2)),
+        Ok(true)
+    );
+    assert_eq!(tag.get("TRCK").unwrap().content().text(), Some("3/12"));
+    assert_eq!(tag.get("TPOS").unwrap().content().text(), Some("1/2"));
+
+    // Cambiar el número conserva el total
+    tag.set_track(4);
+    assert_eq!(tag.total_tracks(), Some(12));
+
+    assert_eq!(apply_totals(&mut tag, None, None, None, None), Ok(false));
+    assert!(apply_totals(&mut tag, None, Some(2), None, None).is_err());
+}
+
+#[test]
+fn test_apply_totals_checks_existing_total() {
+    let mut tag = Tag::new();
+    tag.set_track(3);
+    tag.set_total_tracks(12);
+    tag.set_disc(1);
+    tag.set_total_discs(2);
+
+    // Un número nuevo sin total se compara con el total existente
+    tag.set_track(15);
+    assert!(apply_totals(&mut tag, Some(15), None, None, None).is_err());
+    tag.set_track(12);
+    assert_eq!(
+        apply_totals(&mut tag, Some(12), None, None, None),
+        Ok(false)
+    );
+    tag.set_disc(3);
+    assert!(apply_totals(&mut tag, None, None, Some(3), None).is_err());
+
+    // Con un total nuevo, el existente no importa
+    tag.set_track(15);
+    assert_eq!(
+        apply_totals(&mut tag, Some(15), Some(20), None, None),
+        Ok(true)
+    );
+    assert_eq!(tag.get("TRCK").unwrap().content().text(), Some("15/20"));
+}
+
+#[test]
+fn test_apply_totals_without_number() {
+    let mut tag = Tag::new();
+    assert!(apply_totals(&mut tag, None, Some(12), None, None).is_err());
+    assert!(apply_totals(&mut tag, None, None, None, Some(2)).is_err());
+    assert!(tag.get("TRCK").is_none());
+}
+
+#[test]
+fn test_remove_totals() {
+    let mut tag = Tag::new();
+    tag.set_track(3);
+    tag.set_total_tracks(12);
+    tag.set_disc(1);
+    tag.set_total_discs(2);
+
+    remove_tags(
+        &mut tag,
+        &["track_total".to_string(), "total_temporadas".to_string()],
+    );
+
+    assert_eq!(tag.track(), Some(3));
+    assert_eq!(tag.total_tracks(), None);
+    assert_eq!(tag.disc(), Some(1));
+    assert_eq!(tag.total_discs(), None);
+}
+
+#[test]
+fn test_sort_for_numbering_by_title() {
+    let dir = create_temp_dir("number_title");
+    for (file, title) in [
+        ("a.mp3", Some("Zeta")),
+        ("b.mp3", Some("alfa")),
+        ("c.mp3", None),
+    ] {
+        let mut tag = Tag::new();
+        if let Some(title) = title {
+            tag.set_title(title);
+        }
+        std::fs::write(dir.join(file), b"").unwrap();
+        tag.write_to_path(dir.join(file), id3::Version::Id3v24)
+            .unwrap();
+    }
+
+    let mut files = vec![dir.join("a.mp3"), dir.join("c.mp3"), dir.join("b.mp3")];
+    sort_for_numbering(&mut files, NumberOrder::Title);
+    // Sin título van al final
+    assert_eq!(
+        files,
+        vec![dir.join("b.mp3"), dir.join("a.mp3"), dir.join("c.mp3")]
+    );
+
+    sort_for_numbering(&mut files, NumberOrder::Filename);
+    assert_eq!(
+        files,
+        vec![dir.join("a.mp3"), dir.join("b.mp3"), dir.join("c.mp3")]
+    );
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_number_track() {
+    let mut tag = Tag::new();
+    assert_eq!(number_track(&mut tag, 2, 10), Ok(true));
+    assert_eq!(tag.get("TRCK").unwrap().content().text(), Some("2/10"));
+    assert_eq!(number_track(&mut tag, 2, 10), Ok(false));
+
+    // Renumerar con un total menor que el anterior
+    tag.set_total_tracks(20);
+    tag.set_track(15);
+    assert_eq!(number_track(&mut tag, 3, 4), Ok(true));
+    assert_eq!(tag.get("TRCK").unwrap().content().text(), Some("3/4"));
+}
+
+#[test]
+fn test_field_frame_ids() {
+    let ids = |name: &str| field_frame_ids(name).map(|ids| ids.join(","));
+    assert_eq!(ids("title"), Ok("TIT2".to_string()));
+    assert_eq!(ids("titulo"), Ok("TIT2".to_string()));
+    assert_eq!(ids("carátula"), Ok("APIC".to_string()));
+    assert_eq!(ids("year"), Ok("TYER".to_string()));
+    assert_eq!(ids("fecha"), Ok("TDRC".to_string()));
+    assert_eq!(ids("disc"), Ok("TPOS".to_string()));
+    assert_eq!(ids("chapters"), Ok("CHAP,CTOC".to_string()));
+    assert_eq!(ids("tkey"), Ok("TKEY".to_string()));
+    assert!(field_frame_ids("foo").is_err());
+}
+
+#[test]
+fn test_remove_tags_shares_copy_names() {
+    let mut tag = Tag::new();
+    tag.set_title("Título");
+    tag.set_year(2024);
+    tag.set_text("TDRC", "2024-05-01");
+    tag.set_disc(2);
+
+    assert!(remove_tags(
+        &mut tag,
+        &["titulo".to_string(), "year".to_string(), "disc".to_string()]
+    ));
+    assert_eq!(tag.title(), None);
+    assert_eq!(tag.year(), None);
+    assert_eq!(tag.disc(), None);
+    // `year` no borra la fecha de grabación
+    assert!(tag.date_recorded().is_some());
+}
+
+#[test]
+fn test_copy_tags_all_except() {
+    let mut source = Tag::new();
+    source.set_title("Master");
+    source.set_album("Álbum");
+    add_lyrics(&mut source, "Letra", "spa", "");
+
+    let mut dest = Tag::new();
+    dest.set_title("Antiguo");
+    dest.set_artist("Se conserva");
+
+    let copied = copy_tags(&source, &mut dest, &[], &["lyrics".to_string()]).unwrap();
+
+    assert_eq!(copied, 2);
+    assert_eq!(dest.title(), Some("Master"));
+    assert_eq!(dest.album(), Some("Álbum"));
+    assert_eq!(dest.artist(), Some("Se conserva"));
+    assert_eq!(dest.lyrics().count(), 0);
+}
+
+#[test]
+fn test_copy_tags_only_replaces_frames() {
+    let mut source = Tag::new();
+    source.set_title("Master");
+    add_comment(&mut source, "Nuevo", "spa", "");
+
+    let mut dest = Tag::new();
+    add_comment(&mut dest, "Viejo", "eng", "nota");
+
+    let copied = copy_tags(&source, &mut dest, &["comment".to_string()], &[]).unwrap();
+
+    assert_eq!(copied, 1);
+    assert!(dest.title().is_none());
+    let comments: Vec<_> = dest.comments().collect();
+    assert_eq!(comments.len(), 1);
+    assert_eq!(comments[0].text, "Nuevo");
+}
+
+#[test]
+fn test_copy_tags_invalid_name() {
+    let mut dest = Tag::new();
+    assert!(copy_tags(&Tag::new(), &mut dest, &["foo".to_string()], &[]).is_err());
+}
+
+#[test]
+fn test_parse_picture_selector() {
+    assert_eq!(parse_picture_selector("all"), Ok(PictureSelector::All));
+    assert_eq!(
+        parse_picture_selector("front"),
+        Ok(PictureSelector::Type(PictureType::CoverFront))
+    );
+    assert_eq!(
+        parse_picture_selector("back"),
+        Ok(PictureSelector::Type(PictureType::CoverBack))
+    );
+    assert_eq!(
+        parse_picture_selector("artist"),
+        Ok(PictureSelector::Type(PictureType::Artist))
+    );
+    assert!(parse_picture_selector("portada").is_err());
+}
+
+#[test]
+fn test_extract_pictures() {
+    let dir = create_temp_dir("extract");
+    let file = dir.join("episodio.mp3");
+
+    let mut tag = Tag::new();
+    tag.add_frame(create_picture_frame(vec![0xFF, 0xD8, 0xFF], "image/jpeg"));
+    tag.add_frame(Picture {
+        mime_type: "image/png".to_string(),
+        picture_type: PictureType::CoverBack,
+        description: String::new(),
+        data: vec![0x89, 0x50, 0x4E, 0x47],
+    });
+
+    // Por defecto junto al MP3, con la extensión del tipo MIME
+    let paths = extract_pictures(&tag, &file, PictureSelector::All, None, false).unwrap();
+    assert_eq!(
+        paths,
+        vec![
+            dir.join("episodio-cover-front.jpg"),
+            dir.join("episodio-cover-back.png")
+        ]
+    );
+    assert_eq!(
+        std::fs::read(&paths[1]).unwrap(),
+        vec![0x89, 0x50, 0x4E, 0x47]
+    );
+
+    // Una sola carátula a un archivo sin extensión
+    let output = dir.join("portada");
+    let paths = extract_pictures(
+        &tag,
+        &file,
+        PictureSelector::Type(PictureType::CoverFront),
+        Some(&output),
+        false,
+    )
+    .unwrap();
+    assert_eq!(paths, vec![dir.join("portada.jpg")]);
+
+    // Sin --force no se sobrescribe ni se escribe nada
+    std::fs::write(&paths[0], b"otro").unwrap();
+    assert!(
+        extract_pictures(
+            &tag,
+            &file,
+            PictureSelector::Type(PictureType::CoverFront),
+            Some(&output),
+            false,
+        )
+        .unwrap_err()
+        .to_string()
+        .contains("--force")
+    );
+    assert_eq!(std::fs::read(&paths[0]).unwrap(), b"otro");
+    extract_pictures(
+        &tag,
+        &file,
+        PictureSelector::Type(PictureType::CoverFront),
+        Some(&output),
+        true,
+    )
+    .unwrap();
+    assert_eq!(std::fs::read(&paths[0]).unwrap(), vec![0xFF, 0xD8, 0xFF]);
+
+    assert!(
+        extract_pictures(
+            &tag,
+            &file,
+            PictureSelector::Type(PictureType::Artist),
+            None,
+            false,
+        )
+        .is_err()
+    );
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_extract_pictures_extension_from_content() {
+    let dir = create_temp_dir("extract-sniff");
+    let file = dir.join("episodio.mp3");
+
+    // Declarada como JPEG pero con datos PNG
+    let png = vec![0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A, 0x00];
+    let mut tag = Tag::new();
+    tag.add_frame(create_picture_frame(png.clone(), "image/jpeg"));
+
+    let paths = extract_pictures(&tag, &file, PictureSelector::All, None, false).unwrap();
+    assert_eq!(paths, vec![dir.join("episodio-cover-front.png")]);
+
+    // Una extensión de salida que no corresponde a la imagen se corrige
+    let paths = extract_pictures(
+        &tag,
+        &file,
+        PictureSelector::All,
+        Some(&dir.join("portada.jpg")),
+        false,
+    )
+    .unwrap();
+    assert_eq!(paths, vec![dir.join("portada.png")]);
+    assert_eq!(std::fs::read(&paths[0]).unwrap(), png);
+
+    let paths = extract_pictures(
+        &tag,
+        &file,
+        PictureSelector::All,
+        Some(&dir.join("otra.png")),
+        false,
+    )
+    .unwrap();
+    assert_eq!(paths, vec![dir.join("otra.png")]);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_add_picture_replaces_same_type() {
+    let mut tag = Tag::new();
+    let mime = "image/jpeg";
+
+    assert!(!add_picture(
+        &mut tag,
+        mime,
+        vec![1],
+        PictureType::CoverFront,
+        "Cover"
+    ));
+    assert!(!add_picture(
+        &mut tag,
+        mime,
+        vec![2],
+        PictureType::CoverBack,
+        "Contra"
+    ));
+    assert!(add_picture(
+        &mut tag,
+        mime,
+        vec![3],
+        PictureType::CoverFront,
+        "Nueva"
+    ));
+
+    let pictures: Vec<_> = tag.pictures().collect();
+    assert_eq!(pictures.len(), 2);
+    let front = pictures
+        .iter()
+        .find(|p| p.picture_type == PictureType::CoverFront)
+        .unwrap();
+    assert_eq!(front.data, vec![3]);
+    assert_eq!(front.description, "Nueva");
+}
+
+#[test]
+fn test_remove_picture_by_type_and_position() {
+    let mut tag = Tag::new();
+    for (data, picture_type) in [
+        (1, PictureType::CoverFront),
+        (2, PictureType::CoverBack),
+        (3, PictureType::Artist),
+    ] {
+        add_picture(&mut tag, "image/png", vec![data], picture_type, "");
+    }
+
+    assert_eq!(remove_picture(&mut tag, "back"), Ok(true));
+    assert_eq!(remove_picture(&mut tag, "back"), Ok(false));
+    assert_eq!(remove_picture(&mut tag, "2"), Ok(true));
+    assert!(remove_picture(&mut tag, "2").is_err());
+    assert!(remove_picture(&mut tag, "portada").is_err());
+
+    let pictures: Vec<_> = tag.pictures().collect();
+    assert_eq!(pictures.len(), 1);
+    assert_eq!(pictures[0].picture_type, PictureType::CoverFront);
+}
+
+#[test]
+fn test_remove_tags_single_cover() {
+    let mut tag = Tag::new();
+    add_picture(&mut tag, "image/png", vec![1], PictureType::CoverFront, "");
+    add_picture(&mut tag, "image/png", vec![2], PictureType::Leaflet, "");
+
+    assert!(remove_tags(&mut tag, &["cover:leaflet".to_string()]));
+    assert_eq!(tag.pictures().count(), 1);
+    assert!(!remove_tags(&mut tag, &["cover:leaflet".to_string()]));
+}
+
+#[test]
+fn test_detect_mime_type_gif_bmp() {
+    assert_eq!(detect_mime_type(Path::new("a.gif")).unwrap(), "image/gif");
+    assert_eq!(detect_mime_type(Path::new("a.BMP")).unwrap(), "image/bmp");
+}
+
+#[test]
+fn test_sniff_mime_type() {
+    assert_eq!(
+        sniff_mime_type(&[0xFF, 0xD8, 0xFF, 0xE0]),
+        Some("image/jpeg")
+    );
+    assert_eq!(
+        sniff_mime_type(&[0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A]),
+        Some("image/png")
+    );
+    assert_eq!(
+        sniff_mime_type(b"RIFF\x00\x00\x00\x00WEBPVP8 "),
+        Some("image/webp")
+    );
+    assert_eq!(sniff_mime_type(b"RIFF\x00\x00\x00\x00WAVE"), None);
+    assert_eq!(sniff_mime_type(b"GIF89a"), Some("image/gif"));
+    assert_eq!(sniff_mime_type(b"II*\x00"), None);
+}
+
+#[test]
+fn test_sniff_mime_type_bmp_header() {
+    // Cabecera de archivo (14 bytes) + BITMAPINFOHEADER (40 bytes)
+    let mut bmp = b"BM".to_vec();
+    bmp.extend_from_slice(&58u32.to_le_bytes());
+    bmp.extend_from_slice(&[0; 4]);
+    bmp.extend_from_slice(&54u32.to_le_bytes());
+    bmp.extend_from_slice(&40u32.to_le_bytes());
+    assert_eq!(sniff_mime_type(&bmp), Some("image/bmp"));
+
+    // Un texto que empieza por "BM" no es una imagen
+    assert_eq!(sniff_mime_type(b"BM\x00\x00"), None);
+    assert_eq!(sniff_mime_type(b"BMW Motorrad: manual de usuario"), None);
+
+    // Tamaño de cabecera DIB desconocido
+    let mut unknown_dib = bmp.clone();
+    unknown_dib[14..18].copy_from_slice(&41u32.to_le_bytes());
+    assert_eq!(sniff_mime_type(&unknown_dib), None);
+
+    // Píxeles dentro de las cabeceras
+    let mut bad_offset = bmp.clone();
+    bad_offset[10..14].copy_from_slice(&20u32.to_le_bytes());
+    assert_eq!(sniff_mime_type(&bad_offset), None);
+
+    // Campos reservados distintos de cero
+    let mut reserved = bmp;
+    reserved[6] = 1;
+    assert_eq!(sniff_mime_type(&reserved), None);
+}
+
+#[test]
+fn test_image_mime_type_prefers_content() {
+    let png = [0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A];
+    // Un PNG guardado como .jpg se reconoce como PNG
+    assert_eq!(
+        image_mime_type(Path::new("foto.jpg"), &png),
+        Ok("image/png")
+    );
+    // Una extensión desconocida no importa si el contenido se reconoce
+    assert_eq!(
+        image_mime_type(Path::new("foto.img"), b"GIF87a"),
+        Ok("image/gif")
+    );
+    // Sin cabecera reconocible se usa la extensión
+    assert_eq!(
+        image_mime_type(Path::new("foto.webp"), &[0]),
+        Ok("image/webp")
+    );
+    assert!(image_mime_type(Path::new("foto.tiff"), b"II*\x00").is_err());
+}
+
+#[test]
+fn test_image_mime_mismatch() {
+    let png = [0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A];
+    let warning = image_mime_mismatch(Path::new("foto.jpg"), &png).unwrap();
+    assert!(warning.contains("image/png"));
+    assert!(warning.contains("image/jpeg"));
+
+    assert_eq!(image_mime_mismatch(Path::new("foto.png"), &png), None);
+    // Sin extensión o contenido reconocibles no hay nada que comparar
+    assert_eq!(image_mime_mismatch(Path::new("foto.img"), &png), None);
+    assert_eq!(image_mime_mismatch(Path::new("foto.jpg"), &[0]), None);
+}
+
+#[test]
+fn test_add_cover_art_mismatched_extension() {
+    let mut tag = Tag::new();
+    let png = vec![0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A];
+    add_cover_art(&mut tag, Path::new("portada.jpg"), png).unwrap();
+
+    let pictures: Vec<_> = tag.pictures().collect();
+    assert_eq!(pictures[0].mime_type, "image/png");
+}
+
+/// Codifica una imagen RGBA de prueba en PNG
+fn png_image(width: u32, height: u32) -> Vec<u8> {
+    let image = image::RgbaImage::from_pixel(width, height, image::Rgba([200, 30, 30, 128]));
+    let mut data = Vec::new();
+    image::DynamicImage::ImageRgba8(image)
+        .write_to(
+            &mut std::io::Cursor::new(&mut data),
+            image::ImageFormat::Png,
+        )
+        .unwrap();
+    data
+}
+
+#[test]
+fn test_process_cover_resize_and_jpeg() {
+    let data = png_image(300, 200);
+    let options = CoverProcessing {
+        max_size: Some(150),
+        format: Some(CoverFormat::Jpeg),
+        quality: 80,
+    };
+
+    let processed = process_cover(&data, &options).unwrap();
+
+    assert_eq!(processed.original_dimensions, (300, 200));
+    assert_eq!(processed.dimensions, (150, 100));
+    assert_eq!(processed.mime_type, "image/jpeg");
+    assert_eq!(sniff_mime_type(&processed.data), Some("image/jpeg"));
+}
+
+#[test]
+fn test_process_cover_keeps_small_images() {
+    let data = png_image(100, 100);
+    let options = CoverProcessing {
+        max_size: Some(1400),
+        ..Default::default()
+    };
+
+    let processed = process_cover(&data, &options).unwrap();
+
+    assert_eq!(processed.dimensions, (100, 100));
+    assert_eq!(processed.mime_type, "image/png");
+    // Sin redimensionar ni cambiar de formato no se recodifica
+    assert_eq!(processed.data, data);
+}
+
+#[test]
+fn test_process_cover_keeps_original_format() {
+    let mut data = Vec::new();
+    image::RgbImage::from_pixel(40, 40, image::Rgb([200, 30, 30]))
+        .write_to(
+            &mut std::io::Cursor::new(&mut data),
+            image::ImageFormat::Gif,
+        )
+        .unwrap();
+
+    let processed = process_cover(&data, &CoverProcessing::default()).unwrap();
+    assert_eq!(processed.mime_type, "image/gif");
+    assert_eq!(processed.data, data);
+
+    // Con formato explícito siempre se recodifica
+    let options = CoverProcessing {
+        format: Some(CoverFormat::Png),
+        ..Default::default()
+    };
+    let processed = process_cover(&data, &options).unwrap();
+    assert_eq!(processed.mime_type, "image/png");
+    assert_eq!(sniff_mime_type(&processed.data), Some("image/png"));
+}
+
+#[test]
+fn test_process_cover_invalid_image() {
+    assert!(process_cover(b"no es una imagen", &CoverProcessing::default()).is_err());
+}
+
+#[test]
+fn test_format_bytes() {
+    assert_eq!(format_bytes(512), "512 B");
+    assert_eq!(format_bytes(2048), "2.0 KB");
+    assert_eq!(format_bytes(5 * 1024 * 1024), "5.0 MB");
+}
+
+#[test]
+fn test_find_cover_image() {
+    let dir = create_temp_dir("cover_auto");
+    std::fs::write(dir.join("Folder.PNG"), b"").unwrap();
+    std::fs::write(dir.join("front.jpg"), b"").unwrap();
+    std::fs::write(dir.join("cover.txt"), b"").unwrap();
+
+    let names = |list: &[&str]| list.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+
+    // Se respeta el orden de preferencia y se ignoran las extensiones que no son imágenes
+    let defaults = names(DEFAULT_COVER_NAMES);
+    assert_eq!(
+        find_cover_image(&dir, &defaults),
+        Some(dir.join("Folder.PNG"))
+    );
+    assert_eq!(
+        find_cover_image(&dir, &names(&["front", "folder"])),
+        Some(dir.join("front.jpg"))
+    );
+    assert_eq!(
+        find_cover_image(&dir, &names(&["front.png", "folder.png"])),
+        Some(dir.join("Folder.PNG"))
+    );
+    assert_eq!(find_cover_image(&dir, &names(&["artwork"])), None);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_validate_apple_artwork_valid() {
+    assert!(validate_apple_artwork(&png_image(1400, 1400)).is_empty());
+}
+
+#[test]
+fn test_validate_apple_artwork_dimensions() {
+    let problems = validate_apple_artwork(&png_image(300, 200));
+    assert_eq!(problems.len(), 2);
+    assert!(problems[0].contains("No es cuadrada"));
+    assert!(problems[1].contains("Demasiado pequeña"));
+}
+
+#[test]
+fn test_validate_apple_artwork_colour_space_and_format() {
+    // JPEG en escala de grises
+    let mut gray = Vec::new();
+    image::GrayImage::from_pixel(1400, 1400, image::Luma([90]))
+        .write_with_encoder(image::codecs::jpeg::JpegEncoder::new(&mut gray))
+        .unwrap();
+    let problems = validate_apple_artwork(&gray);
+    assert_eq!(problems.len(), 1);
+    assert!(problems[0].contains("escala de grises"));
+
+    // Cabecera JPEG con cuatro componentes (CMYK)
+    let cmyk = [
+        0xFF, 0xD8, 0xFF, 0xC0, 0x00, 0x14, 0x08, 0x05, 0x78, 0x05, 0x78, 0x04,
+    ];
+    assert!(
+        validate_apple_artwork(&cmyk)
+            .iter()
+            .any(|p| p.contains("CMYK"))
+    );
+
+    assert!(validate_apple_artwork(b"GIF89a")[0].contains("image/gif"));
+    assert_eq!(validate_apple_artwork(b"texto").len(), 1);
+}
+
+/// Tag con título, artista y álbum, que `lint_tag` considera correcto
+fn complete_tag() -> Tag {
+    let mut tag = Tag::new();
+    tag.set_title("Título");
+    tag.set_artist("Artista");
+    tag.set_album("Álbum");
+    tag
+}
+
+fn lint_codes(tag: &Tag) -> Vec<&'static str> {
+    lint_tag(tag).into_iter().map(|issue| issue.code).collect()
+}
+
+#[test]
+fn test_lint_tag_clean() {
+    assert!(lint_tag(&complete_tag()).is_empty());
+}
+
+#[test]
+fn test_lint_tag_missing_fields() {
+    let issues = lint_tag(&Tag::new());
+    let frames: Vec<_> = issues.iter().filter_map(|i| i.frame.as_deref()).collect();
+    assert_eq!(frames, vec!["TIT2", "TPE1", "TALB"]);
+    assert!(issues.iter().all(|i| i.code == "missing-field"));
+}
+
+#[test]
+fn test_lint_tag_duplicates_and_lyrics() {
+    let mut tag = complete_tag();
+    tag.extend([Frame::text("TIT2", "Otro título")]);
+    add_lyrics(&mut tag, "Letra", "spa", "");
+    tag.extend([Frame::with_content(
+        "USLT",
+        Content::Lyrics(id3::frame::Lyrics {
+            lang: "spa".to_string(),
+            description: String::new(),
+            text: "Otra letra".to_string(),
+        }),
+    )]);
+
+    assert_eq!(lint_codes(&tag), vec!["duplicate-frame", "duplicate-frame"]);
+}
+
+#[test]
+fn test_lint_tag_lyrics_in_several_languages() {
+    let mut tag = complete_tag();
+    add_lyrics(&mut tag, "Letra", "spa", "");
+    add_lyrics(&mut tag, "Lyrics", "eng", "");
+
+    assert_eq!(tag.lyrics().count(), 2);
+    assert!(lint_tag(&tag).is_empty());
+}
+
+#[test]
+fn test_lint_tag_dates_and_numbers() {
+    let mut tag = complete_tag();
+    tag.set_text("TDRC", "2024-13-45");
+    tag.set_text("TRCK", "13/12");
+    tag.set_text("TPOS", "uno");
+
+    assert_eq!(
+        lint_codes(&tag),
+        vec!["invalid-date", "number-exceeds-total", "invalid-number"]
+    );
+
+    tag.set_text("TDRC", "ayer");
+    assert_eq!(lint_codes(&tag)[0], "invalid-date");
+    tag.set_text("TDRC", "2024-02-29T10:30");
+    assert_ne!(lint_codes(&tag)[0], "invalid-date");
+}
+
+#[test]
+fn test_lint_tag_text_quality() {
+    let mut tag = complete_tag();
+    tag.set_artist(" Artista ");
+    tag.set_album("CafÃ© del Mar");
+    add_comment(&mut tag, "Itâ€™s", "eng", "");
+
+    assert_eq!(lint_codes(&tag), vec!["whitespace", "mojibake", "mojibake"]);
+}
+
+#[test]
+fn test_lint_tag_pictures_and_obsolete_frames() {
+    let mut tag = complete_tag();
+    tag.add_frame(create_picture_frame(
+        vec![0; LINT_MAX_PICTURE_BYTES + 1],
+        "image/jpeg",
+    ));
+    tag.set_text("TDAT", "2201");
+
+    assert_eq!(
+        lint_codes(&tag),
+        vec!["oversized-picture", "obsolete-frame"]
+    );
+}
+
+#[test]
+fn test_lint_tag_accepts_year_in_v24() {
+    let mut tag = complete_tag();
+    tag.set_year(2024);
+
+    assert!(lint_tag(&tag).is_empty());
+}
+
+#[test]
+fn test_lint_tag_disc_message() {
+    let mut tag = complete_tag();
+    tag.set_text("TPOS", "4/3");
+
+    let issues = lint_tag(&tag);
+    assert_eq!(
+        issues[0].message,
+        "El disco o temporada 4 supera el total 3"
+    );
+}
+
+#[test]
+fn test_remove_all_tags_new_frames() {
+    let mut tag = Tag::new();
+    tag.set_title("T");
+    tag.set_track(3);
+    tag.set_total_tracks(12);
+    add_comment(&mut tag, "hola", "spa", "");
+    add_user_text(&mut tag, "A", "b");
+    add_user_url(&mut tag, "web", "https://example.com");
+    add_synced_lyrics(&mut tag, &[(0, "Hola".to_string())], "spa", "");
+    add_podcast_metadata(
+        &mut tag,
+        &PodcastMetadata {
+            podcast: true,
+            category: Some("Tech"),
+            episode_id: Some("g1"),
+            ..Default::default()
+        },
+    );
+    add_chapter(
+        &mut tag,
+        &NewChapter {
+            start: 0,
+            title: "Intro".to_string(),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    assert!(remove_all_tags(&mut tag));
+    assert_eq!(tag.frames().count(), 0);
+    assert!(!remove_all_tags(&mut tag));
+}
@@ -771,10 +771,10 @@ fn test_cli_cover_webp() {
 fn test_cli_cover_unsupported_format() {
     let mp3_path = create_temp_mp3();
     let temp_dir = std::env::temp_dir();
-    let cover_path = temp_dir.join("test_cover.gif");
+    let cover_path = temp_dir.join("test_cover.tiff");
 
-    // Crear un archivo GIF (no soportado)
-    fs::write(&cover_path, b"GIF89a").expect("Failed to create GIF");
+    // Crear un archivo TIFF (no soportado)
+    fs::write(&cover_path, b"II*\x00").expect("Failed to create TIFF");
 
     let output = Command::new("cargo")
         .args([
@@ -792,7 +792,7 @@ fn test_cli_cover_unsupported_format() {
     // Debe fallar con formato no soportado
     assert!(!output.status.success());
     let stderr = String::from_utf8_lossy(&output.stderr);
-    assert!(stderr.contains("no soportado") || stderr.contains("gif"));
+    assert!(stderr.contains("no soportado") || stderr.contains("tiff"));
 
     cleanup_file(&mp3_path);
     cleanup_file(&cover_path);
@@ -1667,3 +1667,2056 @@ fn test_cli_add_album() {
 
     cleanup_file(&mp3_path);
 }
+
+#[test]
+fn test_cli_show_json() {
+    let mp3_path = create_temp_mp3();
+
+    Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            "edit",
+            mp3_path.to_str().unwrap(),
+            "--title",
+            "JSON Test",
+            "--lyrics",
+            "Line 1\nLine 2",
+        ])
+        .output()
+        .expect("Failed to execute command");
+
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            "show",
+            mp3_path.to_str().unwrap(),
+            "--format",
+            "json",
+        ])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success());
+
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("Salida JSON inválida");
+    // Un solo archivo también produce un array de informes
+    let reports = json.as_array().unwrap();
+    assert_eq!(reports.len(), 1);
+    let json = &reports[0];
+    assert_eq!(json["file"], mp3_path.to_str().unwrap());
+    assert_eq!(json["version"], "ID3v2.4");
+    assert!(json["size"].as_u64().unwrap() > 10);
+    let frames = json["frames"].as_array().unwrap();
+    assert_eq!(frames.len(), 2);
+    assert_eq!(frames[0]["id"], "TIT2");
+    assert_eq!(frames[0]["content"]["text"], "JSON Test");
+    assert_eq!(frames[1]["id"], "USLT");
+    assert_eq!(frames[1]["content"]["lang"], "spa");
+
+    cleanup_file(&mp3_path);
+}
+
+#[test]
+fn test_cli_show_json_without_tags() {
+    let temp_dir = std::env::temp_dir();
+    let id = COUNTER.fetch_add(1, Ordering::SeqCst);
+    let mp3_path = temp_dir.join(format!("test_notag_{}.mp3", id));
+    fs::write(&mp3_path, []).expect("Failed to create temp MP3");
+
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            "show",
+            mp3_path.to_str().unwrap(),
+            "--format",
+            "json",
+        ])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success());
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("Salida JSON inválida");
+    let json = &json[0];
+    assert!(json["version"].is_null());
+    assert!(json["size"].is_null());
+    assert_eq!(json["frames"].as_array().unwrap().len(), 0);
+
+    cleanup_file(&mp3_path);
+}
+
+#[test]
+fn test_cli_edit_multiple_files() {
+    let first = create_temp_mp3();
+    let second = create_temp_mp3();
+
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            "edit",
+            first.to_str().unwrap(),
+            second.to_str().unwrap(),
+            "--album",
+            "Temporada 1",
+        ])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("2 archivo(s) procesado(s), 0 error(es)"));
+
+    for path in [&first, &second] {
+        let tag = Tag::read_from_path(path).expect("Failed to read tag");
+        assert_eq!(tag.album(), Some("Temporada 1"));
+    }
+
+    cleanup_file(&first);
+    cleanup_file(&second);
+}
+
+#[test]
+fn test_cli_remove_multiple_files() {
+    let first = create_temp_mp3();
+    let second = create_temp_mp3();
+    for path in [&first, &second] {
+        let mut tag = Tag::new();
+        tag.set_title("Title");
+        tag.set_artist("Artist");
+        tag.set_album("Album");
+        tag.write_to_path(path, id3::Version::Id3v24).unwrap();
+    }
+
+    // Los tags siguen siendo posicionales; las rutas extra van con -i
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            "remove",
+            first.to_str().unwrap(),
+            "title",
+            "artist",
+            "-i",
+            second.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("2 archivo(s) procesado(s), 0 error(es)"));
+
+    for path in [&first, &second] {
+        let tag = Tag::read_from_path(path).unwrap();
+        assert_eq!(tag.title(), None);
+        assert_eq!(tag.artist(), None);
+        assert_eq!(tag.album(), Some("Album"));
+    }
+
+    cleanup_file(&first);
+    cleanup_file(&second);
+}
+
+#[test]
+fn test_cli_edit_directory_recursive_and_glob() {
+    let dir = std::env::temp_dir().join(format!(
+        "id3cli_batch_{}",
+        COUNTER.fetch_add(1, Ordering::SeqCst)
+    ));
+    fs::create_dir_all(dir.join("cd2")).unwrap();
+    for name in ["01.mp3", "cd2/02.mp3"] {
+        let mp3 = create_temp_mp3();
+        fs::rename(&mp3, dir.join(name)).unwrap();
+    }
+
+    // Sin --recursive solo se procesa el nivel superior
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            "edit",
+            dir.to_str().unwrap(),
+            "--genre",
+            "Podcast",
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success());
+    let tag = Tag::read_from_path(dir.join("01.mp3")).expect("Failed to read tag");
+    assert_eq!(tag.genre(), Some("Podcast"));
+    let tag = Tag::read_from_path(dir.join("cd2/02.mp3")).expect("Failed to read tag");
+    assert_eq!(tag.genre(), None);
+
+    // Con --recursive se recorren los subdirectorios
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            "edit",
+            dir.to_str().unwrap(),
+            "--recursive",
+            "--artist",
+            "Host",
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success());
+    let tag = Tag::read_from_path(dir.join("cd2/02.mp3")).expect("Failed to read tag");
+    assert_eq!(tag.artist(), Some("Host"));
+
+    // Patrón glob entrecomillado (sin expansión del shell)
+    let pattern = dir.join("*/*.mp3");
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            "remove",
+            pattern.to_str().unwrap(),
+            "artist",
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success());
+    let tag = Tag::read_from_path(dir.join("cd2/02.mp3")).expect("Failed to read tag");
+    assert_eq!(tag.artist(), None);
+    let tag = Tag::read_from_path(dir.join("01.mp3")).expect("Failed to read tag");
+    assert_eq!(tag.artist(), Some("Host"));
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_cli_batch_reports_errors() {
+    let mp3_path = create_temp_mp3();
+
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            "edit",
+            mp3_path.to_str().unwrap(),
+            "/tmp/nonexistent_file_12345.mp3",
+            "--title",
+            "Batch",
+        ])
+        .output()
+        .expect("Failed to execute command");
+
+    // El archivo válido se procesa, pero el código de salida indica el error
+    assert!(!output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("no existe"));
+    assert!(stdout.contains("1 archivo(s) procesado(s), 1 error(es)"));
+
+    let tag = Tag::read_from_path(&mp3_path).expect("Failed to read tag");
+    assert_eq!(tag.title(), Some("Batch"));
+
+    cleanup_file(&mp3_path);
+}
+
+#[test]
+fn test_cli_show_multiple_files_json() {
+    let first = create_temp_mp3();
+    let second = create_temp_mp3();
+
+    Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            "edit",
+            first.to_str().unwrap(),
+            "--title",
+            "Uno",
+        ])
+        .output()
+        .expect("Failed to execute command");
+
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            "show",
+            first.to_str().unwrap(),
+            second.to_str().unwrap(),
+            "--format",
+            "json",
+        ])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success());
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("Salida JSON inválida");
+    let reports = json.as_array().unwrap();
+    assert_eq!(reports.len(), 2);
+    assert_eq!(reports[0]["file"], first.to_str().unwrap());
+    assert_eq!(reports[0]["frames"][0]["content"]["text"], "Uno");
+
+    cleanup_file(&first);
+    cleanup_file(&second);
+}
+
+#[test]
+fn test_cli_edit_dry_run() {
+    let mp3_path = create_temp_mp3();
+
+    Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            "edit",
+            mp3_path.to_str().unwrap(),
+            "--title",
+            "Original",
+        ])
+        .output()
+        .expect("Failed to execute command");
+    let original = fs::read(&mp3_path).unwrap();
+
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            "edit",
+            mp3_path.to_str().unwrap(),
+            "--title",
+            "Nuevo",
+            "--album",
+            "Album",
+            "--dry-run",
+        ])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("~ TIT2: Original → Nuevo"));
+    assert!(stdout.contains("+ TALB: Album"));
+
+    // El archivo no debe haberse modificado
+    assert_eq!(fs::read(&mp3_path).unwrap(), original);
+
+    cleanup_file(&mp3_path);
+}
+
+#[test]
+fn test_cli_remove_dry_run() {
+    let mp3_path = create_temp_mp3();
+
+    Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            "edit",
+            mp3_path.to_str().unwrap(),
+            "--title",
+            "Title",
+            "--artist",
+            "Artist",
+        ])
+        .output()
+        .expect("Failed to execute command");
+
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            "remove",
+            mp3_path.to_str().unwrap(),
+            "--all",
+            "-n",
+        ])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("- TIT2: Title"));
+    assert!(stdout.contains("- TPE1: Artist"));
+
+    let tag = Tag::read_from_path(&mp3_path).expect("Failed to read tag");
+    assert_eq!(tag.title(), Some("Title"));
+    assert_eq!(tag.artist(), Some("Artist"));
+
+    cleanup_file(&mp3_path);
+}
+
+#[test]
+fn test_cli_export_import_round_trip() {
+    let source = create_temp_mp3();
+    let target = create_temp_mp3();
+
+    Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            "edit",
+            source.to_str().unwrap(),
+            "--title",
+            "Episodio 1",
+            "--artist",
+            "Host",
+            "--season",
+            "2",
+            "--lyrics",
+            "Línea 1\nLínea 2\n",
+            "--url",
+            "https://example.com",
+            "--compilation",
+        ])
+        .output()
+        .expect("Failed to execute command");
+
+    let output = Command::new("cargo")
+        .args(["run", "--quiet", "--", "export", source.to_str().unwrap()])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success());
+
+    let sidecar = source.with_extension("json");
+    fs::write(&sidecar, &output.stdout).unwrap();
+
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            "import",
+            target.to_str().unwrap(),
+            sidecar.to_str().unwrap(),
+            "--replace",
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success());
+
+    let original = Tag::read_from_path(&source).expect("Failed to read tag");
+    let imported = Tag::read_from_path(&target).expect("Failed to read tag");
+    let original_frames: Vec<_> = original.frames().collect();
+    let imported_frames: Vec<_> = imported.frames().collect();
+    assert_eq!(original_frames, imported_frames);
+
+    cleanup_file(&sidecar);
+    cleanup_file(&source);
+    cleanup_file(&target);
+}
+
+#[test]
+fn test_cli_import_invalid_sidecar() {
+    let mp3_path = create_temp_mp3();
+    let sidecar = mp3_path.with_extension("json");
+    fs::write(&sidecar, r#"{"tittle": "Typo"}"#).unwrap();
+
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            "import",
+            mp3_path.to_str().unwrap(),
+            sidecar.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Sidecar no válido"));
+
+    cleanup_file(&mp3_path);
+    cleanup_file(&sidecar);
+}
+
+/// Helper para crear un directorio temporal con MP3 etiquetados (título, pista)
+fn create_tagged_dir(files: &[(&str, &str, &str)]) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "id3cli_dir_{}_{}",
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::SeqCst)
+    ));
+    fs::create_dir_all(&dir).unwrap();
+    for (name, title, track) in files {
+        let mp3 = create_temp_mp3();
+        let mut tag = Tag::new();
+        tag.set_title(*title);
+        tag.set_album("Album");
+        if !track.is_empty() {
+            tag.set_track(track.parse().unwrap());
+        }
+        tag.write_to_path(&mp3, id3::Version::Id3v24).unwrap();
+        fs::rename(&mp3, dir.join(name)).unwrap();
+    }
+    dir
+}
+
+#[test]
+fn test_cli_rename() {
+    let dir = create_tagged_dir(&[("a.mp3", "Intro", "1"), ("b.mp3", "AC/DC?", "2")]);
+
+    // Modo simulación: no se mueve nada
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            "rename",
+            dir.to_str().unwrap(),
+            "--template",
+            "{album}/{track:02} - {title}.mp3",
+            "--dry-run",
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Album/01 - Intro.mp3"));
+    assert!(dir.join("a.mp3").exists());
+
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            "rename",
+            dir.to_str().unwrap(),
+            "--template",
+            "{album}/{track:02} - {title}.mp3",
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success());
+    assert!(!dir.join("a.mp3").exists());
+    assert!(dir.join("Album/01 - Intro.mp3").exists());
+    assert!(dir.join("Album/02 - AC_DC_.mp3").exists());
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_cli_rename_collision() {
+    let dir = create_tagged_dir(&[("a.mp3", "Same", "1"), ("b.mp3", "Same", "2")]);
+
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            "rename",
+            dir.to_str().unwrap(),
+            "--template",
+            "{title}.mp3",
+        ])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("mismo destino"));
+
+    // El primero se renombra, el segundo se conserva
+    assert!(dir.join("Same.mp3").exists());
+    assert!(dir.join("b.mp3").exists());
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_cli_from_filename() {
+    let dir = std::env::temp_dir().join(format!(
+        "id3cli_ff_{}_{}",
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::SeqCst)
+    ));
+    let album_dir = dir.join("Tech Podcast - Historia");
+    fs::create_dir_all(&album_dir).unwrap();
+    let mp3_path = album_dir.join("05 - El origen de Internet.mp3");
+    fs::rename(create_temp_mp3(), &mp3_path).unwrap();
+
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            "from-filename",
+            dir.to_str().unwrap(),
+            "--recursive",
+            "--pattern",
+            "%artist% - %album%/%track% - %title%",
+        ])
+        .output()
+        .expect("Failed to execute command");
+
+    if !output.status.success() {
+        eprintln!("stderr: {}", String::from_utf8_lossy(&output.stderr));
+    }
+    assert!(output.status.success());
+
+    let tag = Tag::read_from_path(&mp3_path).expect("Failed to read tag");
+    assert_eq!(tag.artist(), Some("Tech Podcast"));
+    assert_eq!(tag.album(), Some("Historia"));
+    assert_eq!(tag.track(), Some(5));
+    assert_eq!(tag.title(), Some("El origen de Internet"));
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_cli_from_filename_no_match() {
+    let mp3_path = create_temp_mp3();
+
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            "from-filename",
+            mp3_path.to_str().unwrap(),
+            "--pattern",
+            "%track% - %title%",
+        ])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("no encaja"));
+
+    cleanup_file(&mp3_path);
+}
+
+#[test]
+fn test_cli_edit_writes_id3v23() {
+    let mp3_path = create_temp_mp3();
+
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            "--id3-version",
+            "2.3",
+            "edit",
+            mp3_path.to_str().unwrap(),
+            "--title",
+            "Compatible",
+            "--date",
+            "2026-01-22",
+        ])
+        .output()
+        .expect("Failed to execute command");
+
+    if !output.status.success() {
+        eprintln!("stderr: {}", String::from_utf8_lossy(&output.stderr));
+    }
+    assert!(output.status.success());
+
+    let data = fs::read(&mp3_path).unwrap();
+    assert_eq!(data[3], 3);
+
+    let tag = Tag::read_from_path(&mp3_path).expect("Failed to read tag");
+    assert_eq!(tag.version(), id3::Version::Id3v23);
+    assert_eq!(tag.title(), Some("Compatible"));
+    assert_eq!(tag.year(), Some(2026));
+    assert_eq!(tag.get("TDAT").unwrap().content().text(), Some("2201"));
+    assert!(tag.get("TDRC").is_none());
+
+    cleanup_file(&mp3_path);
+}
+
+#[test]
+fn test_cli_convert_roundtrip() {
+    let mp3_path = create_temp_mp3();
+
+    let mut tag = Tag::new();
+    tag.set_title("Episodio");
+    tag.set_date_recorded("2026-01-22".parse().unwrap());
+    tag.set_text("TMOO", "Calm");
+    tag.write_to_path(&mp3_path, id3::Version::Id3v24).unwrap();
+
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            "convert",
+            mp3_path.to_str().unwrap(),
+            "--to",
+            "2.3",
+        ])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("TMOO"));
+
+    let tag = Tag::read_from_path(&mp3_path).expect("Failed to read tag");
+    assert_eq!(tag.version(), id3::Version::Id3v23);
+    assert_eq!(tag.year(), Some(2026));
+    assert!(tag.get("TMOO").is_none());
+
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            "convert",
+            mp3_path.to_str().unwrap(),
+            "--to",
+            "2.4",
+        ])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success());
+
+    let tag = Tag::read_from_path(&mp3_path).expect("Failed to read tag");
+    assert_eq!(tag.version(), id3::Version::Id3v24);
+    assert_eq!(tag.title(), Some("Episodio"));
+    assert_eq!(
+        tag.date_recorded().map(|ts| ts.to_string()),
+        Some("2026-01-22".to_string())
+    );
+    assert_eq!(tag.year(), Some(2026));
+
+    let output = Command::new("cargo")
+        .args(["run", "--quiet", "--", "show", mp3_path.to_str().unwrap()])
+        .output()
+        .expect("Failed to execute command");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Año:       2026"));
+
+    cleanup_file(&mp3_path);
+}
+
+#[test]
+fn test_cli_edit_and_remove_raw_frames() {
+    let mp3_path = create_temp_mp3();
+
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            "edit",
+            mp3_path.to_str().unwrap(),
+            "--frame",
+            "TKEY=Am",
+            "--frame",
+            "TBPM=128",
+            "--frame",
+            "WPAY=https://example.com/support",
+        ])
+        .output()
+        .expect("Failed to execute command");
+
+    if !output.status.success() {
+        eprintln!("stderr: {}", String::from_utf8_lossy(&output.stderr));
+    }
+    assert!(output.status.success());
+
+    let tag = Tag::read_from_path(&mp3_path).expect("Failed to read tag");
+    assert_eq!(tag.get("TKEY").unwrap().content().text(), Some("Am"));
+    assert_eq!(tag.get("TBPM").unwrap().content().text(), Some("128"));
+    assert_eq!(
+        tag.get("WPAY").unwrap().content().link(),
+        Some("https://example.com/support")
+    );
+
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            "remove",
+            mp3_path.to_str().unwrap(),
+            "TKEY",
+            "WPAY",
+        ])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success());
+
+    let tag = Tag::read_from_path(&mp3_path).expect("Failed to read tag");
+    assert!(tag.get("TKEY").is_none());
+    assert!(tag.get("WPAY").is_none());
+    assert!(tag.get("TBPM").is_some());
+
+    cleanup_file(&mp3_path);
+}
+
+#[test]
+fn test_cli_edit_rejects_invalid_frame() {
+    let mp3_path = create_temp_mp3();
+
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            "edit",
+            mp3_path.to_str().unwrap(),
+            "--frame",
+            "APIC=cover.jpg",
+        ])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("APIC"));
+
+    cleanup_file(&mp3_path);
+}
+
+#[test]
+fn test_cli_edit_and_remove_user_frames() {
+    let mp3_path = create_temp_mp3();
+
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            "edit",
+            mp3_path.to_str().unwrap(),
+            "--txxx",
+            "MusicBrainz Album Id=f5093c06-23e3-404f-aeaa-40f72885ee3a",
+            "--txxx",
+            "CATALOGO=AT-042",
+            "--wxxx",
+            "Donaciones=https://example.com/?ref=id3cli",
+        ])
+        .output()
+        .expect("Failed to execute command");
+
+    if !output.status.success() {
+        eprintln!("stderr: {}", String::from_utf8_lossy(&output.stderr));
+    }
+    assert!(output.status.success());
+
+    let tag = Tag::read_from_path(&mp3_path).expect("Failed to read tag");
+    assert_eq!(tag.extended_texts().count(), 2);
+    let link = tag.extended_links().next().unwrap();
+    assert_eq!(link.description, "Donaciones");
+    assert_eq!(link.link, "https://example.com/?ref=id3cli");
+
+    let output = Command::new("cargo")
+        .args(["run", "--quiet", "--", "show", mp3_path.to_str().unwrap()])
+        .output()
+        .expect("Failed to execute command");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("CATALOGO: AT-042"));
+    assert!(stdout.contains("Donaciones: https://example.com/?ref=id3cli"));
+
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            "remove",
+            mp3_path.to_str().unwrap(),
+            "txxx:CATALOGO",
+        ])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success());
+
+    let tag = Tag::read_from_path(&mp3_path).expect("Failed to read tag");
+    let texts: Vec<_> = tag.extended_texts().collect();
+    assert_eq!(texts.len(), 1);
+    assert_eq!(texts[0].description, "MusicBrainz Album Id");
+    assert_eq!(tag.extended_links().count(), 1);
+
+    cleanup_file(&mp3_path);
+}
+
+#[test]
+fn test_cli_adds_multiple_comments() {
+    let mp3_path = create_temp_mp3();
+
+    for args in [
+        vec!["--comment", "Grabado en directo"],
+        vec![
+            "--comment",
+            "Recorded live",
+            "--comment-lang",
+            "eng",
+            "--comment-desc",
+            "notes",
+        ],
+    ] {
+        let output = Command::new("cargo")
+            .args(["run", "--quiet", "--", "edit", mp3_path.to_str().unwrap()])
+            .args(&args)
+            .output()
+            .expect("Failed to execute command");
+
+        if !output.status.success() {
+            eprintln!("stderr: {}", String::from_utf8_lossy(&output.stderr));
+        }
+        assert!(output.status.success());
+    }
+
+    let tag = Tag::read_from_path(&mp3_path).expect("Failed to read tag");
+    let comments: Vec<_> = tag.comments().collect();
+    assert_eq!(comments.len(), 2);
+    assert!(
+        comments
+            .iter()
+            .any(|c| c.lang == "spa" && c.text == "Grabado en directo")
+    );
+    assert!(
+        comments
+            .iter()
+            .any(|c| c.lang == "eng" && c.description == "notes" && c.text == "Recorded live")
+    );
+
+    let output = Command::new("cargo")
+        .args(["run", "--quiet", "--", "show", mp3_path.to_str().unwrap()])
+        .output()
+        .expect("Failed to execute command");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Comentario [spa]: Grabado en directo"));
+    assert!(stdout.contains("Comentario [eng] notes: Recorded live"));
+
+    cleanup_file(&mp3_path);
+}
+
+#[test]
+fn test_cli_lyrics_language_and_description() {
+    let mp3_path = create_temp_mp3();
+
+    for args in [
+        vec!["--lyrics", "Primera versión"],
+        vec!["--lyrics", "English lyrics", "--lyrics-lang", "eng"],
+        vec!["--lyrics", "Segunda versión"],
+    ] {
+        let output = Command::new("cargo")
+            .args(["run", "--quiet", "--", "edit", mp3_path.to_str().unwrap()])
+            .args(&args)
+            .output()
+            .expect("Failed to execute command");
+        assert!(output.status.success());
+    }
+
+    let tag = Tag::read_from_path(&mp3_path).expect("Failed to read tag");
+    let lyrics: Vec<_> = tag.lyrics().collect();
+    assert_eq!(lyrics.len(), 2);
+    assert!(
+        lyrics
+            .iter()
+            .any(|l| l.lang == "spa" && l.text == "Segunda versión")
+    );
+    assert!(
+        lyrics
+            .iter()
+            .any(|l| l.lang == "eng" && l.text == "English lyrics")
+    );
+
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            "edit",
+            mp3_path.to_str().unwrap(),
+            "--lyrics",
+            "x",
+            "--lyrics-lang",
+            "english",
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(!output.status.success());
+
+    cleanup_file(&mp3_path);
+}
+
+#[test]
+fn test_cli_lyrics_and_comment_from_file() {
+    let mp3_path = create_temp_mp3();
+    let lyrics_path = mp3_path.with_extension("txt");
+
+    // UTF-8 con BOM y saltos de línea de Windows
+    let mut lyrics = vec![0xEF, 0xBB, 0xBF];
+    lyrics.extend_from_slice("Primera línea\r\nSegunda línea\r\n\r\n".as_bytes());
+    fs::write(&lyrics_path, lyrics).unwrap();
+
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            "edit",
+            mp3_path.to_str().unwrap(),
+            "--lyrics-file",
+            lyrics_path.to_str().unwrap(),
+            "--comment-file",
+            "-",
+        ])
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .and_then(|mut child| {
+            use std::io::Write;
+            child
+                .stdin
+                .take()
+                .unwrap()
+                .write_all("Comentario desde stdin\n".as_bytes())?;
+            child.wait_with_output()
+        })
+        .expect("Failed to execute command");
+
+    if !output.status.success() {
+        eprintln!("stderr: {}", String::from_utf8_lossy(&output.stderr));
+    }
+    assert!(output.status.success());
+
+    let tag = Tag::read_from_path(&mp3_path).expect("Failed to read tag");
+    let lyrics: Vec<_> = tag.lyrics().collect();
+    assert_eq!(lyrics.len(), 1);
+    assert_eq!(lyrics[0].text, "Primera línea\nSegunda línea\n\n");
+    let comment = tag.comments().next().unwrap();
+    assert_eq!(comment.text, "Comentario desde stdin\n");
+
+    cleanup_file(&mp3_path);
+    cleanup_file(&lyrics_path);
+}
+
+#[test]
+fn test_cli_text_file_conflicts_with_inline_value() {
+    let mp3_path = create_temp_mp3();
+
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            "edit",
+            mp3_path.to_str().unwrap(),
+            "--lyrics",
+            "Letra",
+            "--lyrics-file",
+            "letra.txt",
+        ])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(!output.status.success());
+
+    cleanup_file(&mp3_path);
+}
+
+#[test]
+fn test_cli_lrc_import_and_export() {
+    let mp3_path = create_temp_mp3();
+    let lrc_path = mp3_path.with_extension("lrc");
+    let out_path = mp3_path.with_extension("out.lrc");
+    fs::write(
+        &lrc_path,
+        "[ti:Canción]\n[00:05.00]Primera línea\n[00:10.50]Segunda línea\n",
+    )
+    .unwrap();
+
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            "edit",
+            mp3_path.to_str().unwrap(),
+            "--lrc",
+            lrc_path.to_str().unwrap(),
+            "--lyrics-lang",
+            "spa",
+        ])
+        .output()
+        .expect("Failed to execute command");
+
+    if !output.status.success() {
+        eprintln!("stderr: {}", String::from_utf8_lossy(&output.stderr));
+    }
+    assert!(output.status.success());
+
+    let tag = Tag::read_from_path(&mp3_path).expect("Failed to read tag");
+    let sylt = tag.synchronised_lyrics().next().unwrap();
+    assert_eq!(sylt.lang, "spa");
+    assert_eq!(
+        sylt.content,
+        vec![
+            (5000, "Primera línea".to_string()),
+            (10500, "Segunda línea".to_string()),
+        ]
+    );
+
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            "export-lrc",
+            mp3_path.to_str().unwrap(),
+            "-o",
+            out_path.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success());
+    assert_eq!(
+        fs::read_to_string(&out_path).unwrap(),
+        "[00:05.00]Primera línea\n[00:10.50]Segunda línea\n"
+    );
+
+    let output = Command::new("cargo")
+        .args(["run", "--quiet", "--", "show", mp3_path.to_str().unwrap()])
+        .output()
+        .expect("Failed to execute command");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("[00:05.00] Primera línea"));
+
+    cleanup_file(&mp3_path);
+    cleanup_file(&lrc_path);
+    cleanup_file(&out_path);
+}
+
+#[test]
+fn test_cli_export_lrc_without_sylt() {
+    let mp3_path = create_temp_mp3();
+
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            "export-lrc",
+            mp3_path.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("SYLT"));
+
+    cleanup_file(&mp3_path);
+}
+
+#[test]
+fn test_cli_chapters_import_list_and_remove() {
+    let mp3_path = create_temp_mp3();
+    let chapters_path = mp3_path.with_extension("txt");
+    fs::write(
+        &chapters_path,
+        "00:00:00 Intro\n00:05:30 Tema principal\n00:20:00 Despedida\n",
+    )
+    .unwrap();
+
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            "chapters",
+            "import",
+            mp3_path.to_str().unwrap(),
+            chapters_path.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+
+    if !output.status.success() {
+        eprintln!("stderr: {}", String::from_utf8_lossy(&output.stderr));
+    }
+    assert!(output.status.success());
+
+    let tag = Tag::read_from_path(&mp3_path).expect("Failed to read tag");
+    assert_eq!(tag.chapters().count(), 3);
+    assert_eq!(tag.tables_of_contents().next().unwrap().elements.len(), 3);
+
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            "chapters",
+            "add",
+            mp3_path.to_str().unwrap(),
+            "--start",
+            "00:10:00",
+            "--end",
+            "00:12:00",
+            "--title",
+            "Patrocinio",
+            "--url",
+            "https://example.com",
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success());
+
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            "chapters",
+            "list",
+            mp3_path.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Capítulos: 4"));
+    assert!(stdout.contains("00:10:00.000 → 00:12:00.000  Patrocinio"));
+
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            "chapters",
+            "remove",
+            mp3_path.to_str().unwrap(),
+            "1",
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success());
+
+    let tag = Tag::read_from_path(&mp3_path).expect("Failed to read tag");
+    assert_eq!(tag.chapters().count(), 3);
+    assert!(tag.chapters().all(|c| c.start_time > 0));
+
+    cleanup_file(&mp3_path);
+    cleanup_file(&chapters_path);
+}
+
+#[test]
+fn test_cli_podcast_frames() {
+    let mp3_path = create_temp_mp3();
+
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            "edit",
+            mp3_path.to_str().unwrap(),
+            "--podcast",
+            "--category",
+            "Technology",
+            "--long-description",
+            "Episodio sobre Rust",
+            "--keywords",
+            "rust,cli",
+            "--episode-id",
+            "atareao-42",
+            "--feed-url",
+            "https://example.com/feed.xml",
+        ])
+        .output()
+        .expect("Failed to execute command");
+
+    if !output.status.success() {
+        eprintln!("stderr: {}", String::from_utf8_lossy(&output.stderr));
+    }
+    assert!(output.status.success());
+
+    // Releer desde disco: PCST debe sobrevivir a la escritura
+    let tag = Tag::read_from_path(&mp3_path).expect("Failed to read tag");
+    assert!(tag.get("PCST").is_some());
+    assert_eq!(
+        tag.get("TCAT").unwrap().content().text(),
+        Some("Technology")
+    );
+    assert_eq!(
+        tag.get("TGID").unwrap().content().text(),
+        Some("atareao-42")
+    );
+    assert_eq!(
+        tag.get("WFED").unwrap().content().link(),
+        Some("https://example.com/feed.xml")
+    );
+
+    let output = Command::new("cargo")
+        .args(["run", "--quiet", "--", "show", mp3_path.to_str().unwrap()])
+        .output()
+        .expect("Failed to execute command");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Podcast: Sí"));
+    assert!(stdout.contains("Feed: https://example.com/feed.xml"));
+
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            "remove",
+            mp3_path.to_str().unwrap(),
+            "podcast",
+            "keywords",
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success());
+
+    let tag = Tag::read_from_path(&mp3_path).expect("Failed to read tag");
+    assert!(tag.get("PCST").is_none());
+    assert!(tag.get("TKWD").is_none());
+    assert!(tag.get("TCAT").is_some());
+
+    cleanup_file(&mp3_path);
+}
+
+#[test]
+fn test_cli_edit_track_and_disc_totals() {
+    let mp3_path = create_temp_mp3();
+
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            "edit",
+            mp3_path.to_str().unwrap(),
+            "--track",
+            "3/12",
+            "--season",
+            "1",
+            "--disc-total",
+            "2",
+        ])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success());
+
+    let tag = Tag::read_from_path(&mp3_path).unwrap();
+    assert_eq!(tag.get("TRCK").unwrap().content().text(), Some("3/12"));
+    assert_eq!(tag.get("TPOS").unwrap().content().text(), Some("1/2"));
+
+    let output = Command::new("cargo")
+        .args(["run", "--quiet", "--", "show", mp3_path.to_str().unwrap()])
+        .output()
+        .expect("Failed to execute command");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Pista:     3/12"));
+    assert!(stdout.contains("Temporada: 1/2"));
+
+    // Un total menor que el número de pista es un error
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            "edit",
+            mp3_path.to_str().unwrap(),
+            "--track-total",
+            "2",
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(!output.status.success());
+
+    // También lo es un número de pista mayor que el total existente
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            "edit",
+            mp3_path.to_str().unwrap(),
+            "--track",
+            "15",
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("supera el total 12"));
+
+    let tag = Tag::read_from_path(&mp3_path).unwrap();
+    assert_eq!(tag.get("TRCK").unwrap().content().text(), Some("3/12"));
+
+    cleanup_file(&mp3_path);
+}
+
+#[test]
+fn test_cli_number_directory() {
+    let dir = std::env::temp_dir().join(format!(
+        "id3cli_number_{}",
+        COUNTER.fetch_add(1, Ordering::SeqCst)
+    ));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+
+    for (file, title) in [("01.mp3", "Zeta"), ("02.mp3", "Alfa")] {
+        let mut tag = Tag::new();
+        tag.set_title(title);
+        fs::write(dir.join(file), b"").unwrap();
+        tag.write_to_path(dir.join(file), id3::Version::Id3v24)
+            .unwrap();
+    }
+
+    // Simulación: no se modifica nada
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            "number",
+            "--dir",
+            dir.to_str().unwrap(),
+            "--dry-run",
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("+ TRCK: 1/2"));
+    assert!(
+        Tag::read_from_path(dir.join("01.mp3"))
+            .unwrap()
+            .track()
+            .is_none()
+    );
+
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            "number",
+            "--dir",
+            dir.to_str().unwrap(),
+            "--sort",
+            "title",
+            "--start",
+            "5",
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success());
+
+    let track = |file: &str| {
+        let tag = Tag::read_from_path(dir.join(file)).unwrap();
+        tag.get("TRCK")
+            .unwrap()
+            .content()
+            .text()
+            .unwrap()
+            .to_string()
+    };
+    assert_eq!(track("02.mp3"), "5/6");
+    assert_eq!(track("01.mp3"), "6/6");
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_cli_copy_tags() {
+    let source = create_temp_mp3();
+    let dest1 = create_temp_mp3();
+    let dest2 = create_temp_mp3();
+
+    let mut tag = Tag::new();
+    tag.set_title("Master");
+    tag.set_album("Álbum");
+    tag.set_artist("Artista");
+    tag.write_to_path(&source, id3::Version::Id3v24).unwrap();
+
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            "copy",
+            source.to_str().unwrap(),
+            dest1.to_str().unwrap(),
+            dest2.to_str().unwrap(),
+            "--only",
+            "title,album",
+        ])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success());
+    for dest in [&dest1, &dest2] {
+        let tag = Tag::read_from_path(dest).unwrap();
+        assert_eq!(tag.title(), Some("Master"));
+        assert_eq!(tag.album(), Some("Álbum"));
+        assert!(tag.artist().is_none());
+    }
+
+    // Un nombre de tag desconocido se rechaza antes de tocar los destinos
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            "copy",
+            source.to_str().unwrap(),
+            dest1.to_str().unwrap(),
+            "--except",
+            "foo",
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(!output.status.success());
+
+    cleanup_file(&source);
+    cleanup_file(&dest1);
+    cleanup_file(&dest2);
+}
+
+#[test]
+fn test_cli_cover_extract() {
+    let mp3_path = create_temp_mp3();
+    let cover_path = mp3_path.with_extension("png");
+    let png_data = vec![0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A];
+    fs::write(&cover_path, &png_data).expect("Failed to create PNG");
+
+    Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            "edit",
+            mp3_path.to_str().unwrap(),
+            "--cover",
+            cover_path.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+
+    let output_path = mp3_path.with_extension("portada.extracted");
+    let extracted = mp3_path.with_extension("portada.png");
+    let extract = |force: bool| {
+        let mut args = vec![
+            "run",
+            "--quiet",
+            "--",
+            "cover",
+            "extract",
+            mp3_path.to_str().unwrap(),
+            "-o",
+            output_path.to_str().unwrap(),
+        ];
+        if force {
+            args.push("--force");
+        }
+        Command::new("cargo")
+            .args(args)
+            .output()
+            .expect("Failed to execute command")
+    };
+
+    let output = extract(false);
+    assert!(output.status.success());
+    // La extensión .extracted se corrige a la de la imagen; el contenido es
+    // el PNG original
+    assert!(!output_path.exists());
+    assert_eq!(fs::read(&extracted).unwrap(), png_data);
+
+    // Sin --force no se sobrescribe
+    fs::write(&extracted, b"otro").unwrap();
+    let output = extract(false);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--force"));
+    assert_eq!(fs::read(&extracted).unwrap(), b"otro");
+
+    let output = extract(true);
+    assert!(output.status.success());
+    assert_eq!(fs::read(&extracted).unwrap(), png_data);
+
+    // No hay contraportada
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            "cover",
+            "extract",
+            mp3_path.to_str().unwrap(),
+            "--type",
+            "back",
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(!output.status.success());
+
+    cleanup_file(&mp3_path);
+    cleanup_file(&cover_path);
+    cleanup_file(&extracted);
+}
+
+#[test]
+fn test_cli_cover_type_and_description() {
+    let mp3_path = create_temp_mp3();
+    let cover_path = mp3_path.with_extension("jpg");
+    fs::write(&cover_path, [0xFF, 0xD8, 0xFF, 0xE0]).expect("Failed to create JPG");
+
+    for cover_type in ["front", "back", "back"] {
+        let output = Command::new("cargo")
+            .args([
+                "run",
+                "--quiet",
+                "--",
+                "edit",
+                mp3_path.to_str().unwrap(),
+                "--cover",
+                cover_path.to_str().unwrap(),
+                "--cover-type",
+                cover_type,
+                "--cover-desc",
+                "Trasera",
+            ])
+            .output()
+            .expect("Failed to execute command");
+        assert!(output.status.success());
+    }
+
+    // La segunda contraportada sustituye a la primera
+    let tag = Tag::read_from_path(&mp3_path).unwrap();
+    let pictures: Vec<_> = tag.pictures().collect();
+    assert_eq!(pictures.len(), 2);
+    assert!(pictures.iter().all(|p| p.description == "Trasera"));
+
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            "remove",
+            mp3_path.to_str().unwrap(),
+            "cover:back",
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success());
+
+    let tag = Tag::read_from_path(&mp3_path).unwrap();
+    let pictures: Vec<_> = tag.pictures().collect();
+    assert_eq!(pictures.len(), 1);
+    assert_eq!(
+        pictures[0].picture_type,
+        id3::frame::PictureType::CoverFront
+    );
+
+    cleanup_file(&mp3_path);
+    cleanup_file(&cover_path);
+}
+
+#[test]
+fn test_cli_cover_sniffed_mime_type() {
+    let mp3_path = create_temp_mp3();
+    // Un PNG con extensión .jpg
+    let cover_path = mp3_path.with_extension("jpg");
+    let png_data = vec![0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A];
+    fs::write(&cover_path, png_data).expect("Failed to create PNG");
+
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            "edit",
+            mp3_path.to_str().unwrap(),
+            "--cover",
+            cover_path.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("image/png"));
+
+    let tag = Tag::read_from_path(&mp3_path).unwrap();
+    let pictures: Vec<_> = tag.pictures().collect();
+    assert_eq!(pictures[0].mime_type, "image/png");
+
+    cleanup_file(&mp3_path);
+    cleanup_file(&cover_path);
+}
+
+#[test]
+fn test_cli_cover_gif() {
+    let mp3_path = create_temp_mp3();
+    let cover_path = mp3_path.with_extension("gif");
+    fs::write(&cover_path, b"GIF89a").expect("Failed to create GIF");
+
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            "edit",
+            mp3_path.to_str().unwrap(),
+            "--cover",
+            cover_path.to_str().unwrap(),
+        ])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success());
+    let tag = Tag::read_from_path(&mp3_path).unwrap();
+    let pictures: Vec<_> = tag.pictures().collect();
+    assert_eq!(pictures[0].mime_type, "image/gif");
+
+    cleanup_file(&mp3_path);
+    cleanup_file(&cover_path);
+}
+
+#[test]
+fn test_cli_cover_resize_and_recompress() {
+    let mp3_path = create_temp_mp3();
+    let cover_path = mp3_path.with_extension("png");
+    image::RgbImage::from_pixel(600, 300, image::Rgb([10, 120, 200]))
+        .save(&cover_path)
+        .expect("Failed to create PNG");
+
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            "edit",
+            mp3_path.to_str().unwrap(),
+            "--cover",
+            cover_path.to_str().unwrap(),
+            "--cover-max-size",
+            "200",
+            "--cover-format",
+            "jpeg",
+            "--cover-quality",
+            "70",
+        ])
+        .output()
+        .expect("Failed to execute command");
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("600×300 → 200×100"));
+    // El cambio de formato no debe avisar de discrepancia con la extensión
+    assert!(!String::from_utf8_lossy(&output.stderr).contains("⚠️"));
+
+    let tag = Tag::read_from_path(&mp3_path).unwrap();
+    let picture = tag.pictures().next().unwrap();
+    assert_eq!(picture.mime_type, "image/jpeg");
+    let image = image::load_from_memory(&picture.data).unwrap();
+    assert_eq!((image.width(), image.height()), (200, 100));
+
+    cleanup_file(&mp3_path);
+    cleanup_file(&cover_path);
+}
+
+#[test]
+fn test_cli_cover_auto() {
+    let dir = std::env::temp_dir().join(format!(
+        "id3cli_cover_auto_{}",
+        COUNTER.fetch_add(1, Ordering::SeqCst)
+    ));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(dir.join("disco2")).unwrap();
+
+    let minimal_id3 = [0x49, 0x44, 0x33, 0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00];
+    for file in ["01.mp3", "02.mp3", "disco2/01.mp3"] {
+        fs::write(dir.join(file), minimal_id3).unwrap();
+    }
+    fs::write(dir.join("cover.jpg"), [0xFF, 0xD8, 0xFF, 0xE0]).unwrap();
+    fs::write(
+        dir.join("disco2/folder.png"),
+        [0x89, 0x50, 0x4E, 0x47, 0x0D, 0x0A, 0x1A, 0x0A],
+    )
+    .unwrap();
+
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            "edit",
+            dir.to_str().unwrap(),
+            "--recursive",
+            "--cover-auto",
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success());
+
+    let mime = |file: &str| {
+        let tag = Tag::read_from_path(dir.join(file)).unwrap();
+        tag.pictures().next().unwrap().mime_type.clone()
+    };
+    assert_eq!(mime("01.mp3"), "image/jpeg");
+    assert_eq!(mime("02.mp3"), "image/jpeg");
+    assert_eq!(mime("disco2/01.mp3"), "image/png");
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_cli_cover_auto_bare_filename() {
+    let dir = std::env::temp_dir().join(format!(
+        "id3cli_cover_auto_bare_{}",
+        COUNTER.fetch_add(1, Ordering::SeqCst)
+    ));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+
+    let minimal_id3 = [0x49, 0x44, 0x33, 0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00];
+    fs::write(dir.join("01.mp3"), minimal_id3).unwrap();
+    fs::write(dir.join("cover.jpg"), [0xFF, 0xD8, 0xFF, 0xE0]).unwrap();
+
+    // Ejecutar desde el directorio del MP3 con un nombre sin ruta
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--manifest-path",
+            concat!(env!("CARGO_MANIFEST_DIR"), "/Cargo.toml"),
+            "--",
+            "edit",
+            "01.mp3",
+            "--cover-auto",
+        ])
+        .current_dir(&dir)
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success());
+
+    let tag = Tag::read_from_path(dir.join("01.mp3")).unwrap();
+    assert_eq!(tag.pictures().next().unwrap().mime_type, "image/jpeg");
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_cli_cover_auto_validate_every_file() {
+    let dir = std::env::temp_dir().join(format!(
+        "id3cli_cover_auto_validate_{}",
+        COUNTER.fetch_add(1, Ordering::SeqCst)
+    ));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+
+    let minimal_id3 = [0x49, 0x44, 0x33, 0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00];
+    for file in ["01.mp3", "02.mp3"] {
+        fs::write(dir.join(file), minimal_id3).unwrap();
+    }
+    image::RgbImage::from_pixel(100, 100, image::Rgb([10, 120, 200]))
+        .save(dir.join("cover.png"))
+        .expect("Failed to create PNG");
+
+    // La carátula es demasiado pequeña: el error cuenta en cada archivo
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            "edit",
+            dir.to_str().unwrap(),
+            "--cover-auto",
+            "--cover-validate",
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(!output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stderr)
+            .matches("Error:")
+            .count(),
+        2
+    );
+    assert!(String::from_utf8_lossy(&output.stdout).contains("2 error(es)"));
+
+    for file in ["01.mp3", "02.mp3"] {
+        let tag = Tag::read_from_path(dir.join(file)).unwrap();
+        assert_eq!(tag.pictures().count(), 0);
+    }
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_cli_cover_validate() {
+    let mp3_path = create_temp_mp3();
+    let cover_path = mp3_path.with_extension("png");
+    image::RgbImage::from_pixel(1600, 1200, image::Rgb([10, 120, 200]))
+        .save(&cover_path)
+        .expect("Failed to create PNG");
+
+    // La imagen no es cuadrada: no se modifica el archivo
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            "edit",
+            mp3_path.to_str().unwrap(),
+            "--cover",
+            cover_path.to_str().unwrap(),
+            "--cover-validate",
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("No es cuadrada"));
+    assert!(
+        Tag::read_from_path(&mp3_path)
+            .unwrap()
+            .pictures()
+            .next()
+            .is_none()
+    );
+
+    // Un cuadrado de 1500×1500 es válido
+    image::RgbImage::from_pixel(1500, 1500, image::Rgb([10, 120, 200]))
+        .save(&cover_path)
+        .expect("Failed to create PNG");
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            "edit",
+            mp3_path.to_str().unwrap(),
+            "--cover",
+            cover_path.to_str().unwrap(),
+            "--cover-validate",
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Carátula válida"));
+
+    // Validar la carátula ya incrustada
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            "edit",
+            mp3_path.to_str().unwrap(),
+            "--cover-validate",
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success());
+
+    cleanup_file(&mp3_path);
+    cleanup_file(&cover_path);
+}
+
+#[test]
+fn test_cli_lint() {
+    let good = create_temp_mp3();
+    let bad = create_temp_mp3();
+
+    let mut tag = Tag::new();
+    tag.set_title("Título");
+    tag.set_artist("Artista");
+    tag.set_album("Álbum");
+    tag.write_to_path(&good, id3::Version::Id3v24).unwrap();
+
+    let mut tag = Tag::new();
+    tag.set_title(" Título");
+    tag.write_to_path(&bad, id3::Version::Id3v24).unwrap();
+
+    let output = Command::new("cargo")
+        .args(["run", "--quiet", "--", "lint", good.to_str().unwrap()])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("sin problemas"));
+
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            "lint",
+            good.to_str().unwrap(),
+            bad.to_str().unwrap(),
+            "--format",
+            "json",
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(!output.status.success());
+
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("Invalid JSON output");
+    assert_eq!(json[0]["issues"].as_array().unwrap().len(), 0);
+    let codes: Vec<&str> = json[1]["issues"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|issue| issue["code"].as_str().unwrap())
+        .collect();
+    assert_eq!(codes, vec!["missing-field", "missing-field", "whitespace"]);
+
+    cleanup_file(&good);
+    cleanup_file(&bad);
+}
+
+#[test]
+fn test_cli_lint_after_edit_year() {
+    let mp3_path = create_temp_mp3();
+
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--",
+            "edit",
+            mp3_path.to_str().unwrap(),
+            "--title",
+            "Título",
+            "--artist",
+            "Artista",
+            "--album",
+            "Álbum",
+            "--year",
+            "2020",
+        ])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success());
+
+    // Un archivo editado por id3cli no debe tener problemas de lint
+    let output = Command::new("cargo")
+        .args(["run", "--quiet", "--", "lint", mp3_path.to_str().unwrap()])
+        .output()
+        .expect("Failed to execute command");
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("sin problemas"));
+
+    cleanup_file(&mp3_path);
+}